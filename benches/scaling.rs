@@ -0,0 +1,57 @@
+//! Measures how each day scales, on inputs made up by its generator at a few sizes
+
+use advent_of_code_2022::{DynSolution, Part, SOLUTIONS};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+/// A few sizes to generate a day's input at, spread below the largest it supports
+fn sizes(s: &dyn DynSolution) -> Vec<usize> {
+    let range = s.generate_sizes();
+    let mut sizes = [64, 16, 4]
+        .map(|d| (range.end() / d).max(*range.start()))
+        .to_vec();
+    sizes.dedup();
+    sizes
+}
+
+/// Parts that take just as long whatever the size, such as searching a fixed area
+const SKIP: &[(u32, Part)] = &[(15, Part::B)];
+
+fn scaling(c: &mut Criterion) {
+    for &s in SOLUTIONS {
+        let day = s.day();
+        let mut group = c.benchmark_group(format!("day{day:02}"));
+        group.sample_size(10);
+
+        for size in sizes(s) {
+            let input = match s.generate(size, 0) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("skipping day{day:02} at size {size}: {e:#}");
+                    continue;
+                }
+            };
+            group.throughput(Throughput::Elements(size.try_into().unwrap_or(u64::MAX)));
+
+            group.bench_with_input(BenchmarkId::new("parse", size), &input, |b, input| {
+                b.iter(|| s.parse_only(input));
+            });
+
+            let parsed = s.with_parsed(&input, &mut |solve| {
+                for part in Part::ALL {
+                    if !SKIP.contains(&(day, part)) {
+                        group.bench_function(BenchmarkId::new(part.to_string(), size), |b| {
+                            b.iter(|| solve(part));
+                        });
+                    }
+                }
+            });
+            if let Err(e) = parsed {
+                eprintln!("skipping day{day:02} at size {size}: {e:#}");
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, scaling);
+criterion_main!(benches);
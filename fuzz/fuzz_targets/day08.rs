@@ -0,0 +1,9 @@
+#![no_main]
+
+use advent_of_code_2022::{day08::Day08, DynSolution, Part};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    // Bad input should be an error, so only a panic or a hang is a failure
+    let _ = Day08.run(input, &Part::ALL);
+});
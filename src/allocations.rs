@@ -0,0 +1,126 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering::Relaxed},
+};
+
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// A global allocator that counts every allocation and the most memory in use at once, on top of
+/// the system allocator
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn allocated(size: usize) {
+        COUNT.fetch_add(1, Relaxed);
+        let current = CURRENT.fetch_add(size, Relaxed) + size;
+        PEAK.fetch_max(current, Relaxed);
+    }
+}
+
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: the caller upholds `GlobalAlloc::alloc`'s contract, which is passed on as is
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: as for `alloc`
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            Self::allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        // SAFETY: `ptr` came from this allocator, and so from `System`, with this layout
+        unsafe { System.dealloc(ptr, layout) };
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        // SAFETY: as for `dealloc`, with the caller upholding `GlobalAlloc::realloc`'s contract
+        let new = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new.is_null() {
+            // Count the move as freeing the old block and allocating the new one
+            CURRENT.fetch_sub(layout.size(), Relaxed);
+            Self::allocated(new_size);
+        }
+        new
+    }
+}
+
+/// The allocations made during one step, by every thread
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocations {
+    pub count: u64,
+    /// The most memory in use at once on top of what was already in use when the step started
+    pub peak_bytes: usize,
+}
+
+/// Counts the allocations made between starting and finishing it, which are all zero unless a
+/// [`CountingAllocator`] is the global allocator. Only one step can be tracked at a time.
+#[derive(Debug)]
+pub struct Tracker {
+    count: u64,
+    current: usize,
+}
+
+impl Tracker {
+    #[must_use]
+    pub fn start() -> Self {
+        let current = CURRENT.load(Relaxed);
+        PEAK.store(current, Relaxed);
+        Self {
+            count: COUNT.load(Relaxed),
+            current,
+        }
+    }
+
+    #[must_use]
+    pub fn finish(self) -> Allocations {
+        Allocations {
+            count: COUNT.load(Relaxed) - self.count,
+            peak_bytes: PEAK.load(Relaxed).saturating_sub(self.current),
+        }
+    }
+}
+
+/// Formats a number of bytes in the most readable unit
+#[must_use]
+pub fn format_bytes(bytes: usize) -> String {
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if size < 1024.0 {
+            return if unit == "B" {
+                format!("{bytes}B")
+            } else {
+                format!("{size:.2}{unit}")
+            };
+        }
+        size /= 1024.0;
+    }
+    format!("{size:.2}GiB")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_bytes() {
+        assert_eq!(format_bytes(0), "0B");
+        assert_eq!(format_bytes(1023), "1023B");
+        assert_eq!(format_bytes(1536), "1.50KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.00MiB");
+        assert_eq!(format_bytes(5 << 30), "5.00GiB");
+    }
+}
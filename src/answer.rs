@@ -0,0 +1,169 @@
+use std::{
+    convert::Infallible,
+    fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The result of solving one part of a puzzle
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// A multi-line picture, such as the `#`/`.` output of a CRT
+    Image(String),
+    /// The letters read from a picture, along with the picture. It is the same answer as its text,
+    /// which is how it is displayed, stored and submitted.
+    Letters {
+        text: String,
+        image: String,
+    },
+}
+
+impl Answer {
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Integer(_) => "integer",
+            Self::Text(_) => "text",
+            Self::Image(_) => "image",
+            Self::Letters { .. } => "letters",
+        }
+    }
+
+    /// The picture behind the answer, if there is one
+    #[must_use]
+    pub fn image(&self) -> Option<&str> {
+        match self {
+            Self::Image(image) | Self::Letters { image, .. } => Some(image),
+            Self::Integer(_) | Self::Text(_) => None,
+        }
+    }
+
+    /// What the answer is compared by, which is the same for letters as for their text
+    fn key(&self) -> (u8, Option<i128>, Option<&str>) {
+        match self {
+            Self::Integer(i) => (0, Some(*i), None),
+            Self::Text(s) | Self::Letters { text: s, .. } => (1, None, Some(s)),
+            Self::Image(s) => (2, None, Some(s)),
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Answer {}
+
+impl Hash for Answer {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Integer(i) => write!(f, "{i}"),
+            Self::Text(s) | Self::Image(s) | Self::Letters { text: s, .. } => write!(f, "{s}"),
+        }
+    }
+}
+
+/// Reads back a displayed answer, treating anything over multiple lines as an image and anything
+/// that looks like a number as an integer
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(if s.trim_end().contains('\n') {
+            Self::Image(s.to_owned())
+        } else if let Ok(i) = s.parse() {
+            Self::Integer(i)
+        } else {
+            Self::Text(s.to_owned())
+        })
+    }
+}
+
+macro_rules! integer_answer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(i: $t) -> Self {
+                    Self::Integer(i.into())
+                }
+            }
+        )*
+    };
+}
+
+integer_answer!(i32, i64, u32, u64);
+
+impl From<usize> for Answer {
+    fn from(i: usize) -> Self {
+        // usize is at most 64 bits on every supported target
+        Self::Integer(i as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Answer {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let Ok(answer) = s.parse();
+        Ok(answer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_strings() {
+        for answer in [
+            Answer::from(-12),
+            Answer::from(1_514_285_714_288_u64),
+            Answer::from("CMZ".to_owned()),
+            Answer::Image("#.\n.#\n".to_owned()),
+        ] {
+            assert_eq!(answer.to_string().parse(), Ok(answer));
+        }
+    }
+
+    #[test]
+    fn kinds() {
+        assert_eq!(Answer::from(7_usize).kind(), "integer");
+        assert_eq!(Answer::from("MCD".to_owned()).kind(), "text");
+        assert_eq!(Answer::Image("#\n".to_owned()).kind(), "image");
+    }
+
+    #[test]
+    fn letters_are_their_text() {
+        let letters = Answer::Letters {
+            text: "L".to_owned(),
+            image: "#.\n##\n".to_owned(),
+        };
+        assert_eq!(letters.to_string(), "L");
+        assert_eq!(letters.to_string().parse(), Ok(letters.clone()));
+        assert_eq!(letters.image(), Some("#.\n##\n"));
+        assert_ne!(letters, Answer::Image("#.\n##\n".to_owned()));
+    }
+}
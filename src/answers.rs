@@ -0,0 +1,348 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{Answer, Part};
+
+/// Where the accepted answers are kept unless told otherwise
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+const HEADER: &str = "\
+# Accepted answers for the puzzle inputs in resources/, checked by `runner verify`.
+# `runner submit` adds to this, and records rejected answers so they are never sent twice, and
+# when the site next allows an answer after asking to wait.
+# Each is kept against a digest of the input it was for, so other inputs are never checked.
+
+";
+
+/// Accepted and rejected answers, keyed by day, part and the [`digest`](crate::inputs::digest) of
+/// the input they were for
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnswerStore {
+    #[serde(default, rename = "answer")]
+    answers: Vec<Entry>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    rejected: Vec<Rejected>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    wait: Vec<Wait>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub answer: Answer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rejected {
+    pub day: u32,
+    pub part: Part,
+    pub input: String,
+    pub answer: Answer,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<Hint>,
+}
+
+/// A part the site asked to wait before answering again
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Wait {
+    pub day: u32,
+    pub part: Part,
+    /// Seconds since the Unix epoch before which no answer is sent
+    pub until: u64,
+}
+
+/// Which way a rejected answer was out, when the site says
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooHigh => write!(f, "too high"),
+            Self::TooLow => write!(f, "too low"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pass => write!(f, "PASS"),
+            Self::Fail { .. } => write!(f, "FAIL"),
+            Self::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+impl AnswerStore {
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("unable to read answers from {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("invalid answers in {}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, anyhow::Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let content = format!("{HEADER}{}", toml::to_string_pretty(self)?);
+        fs::write(path, content)
+            .with_context(|| format!("unable to write answers to {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn expected(&self, day: u32, part: Part, input: &str) -> Option<&Answer> {
+        self.answers
+            .iter()
+            .find(|e| e.day == day && e.part == part && e.input == input)
+            .map(|e| &e.answer)
+    }
+
+    #[must_use]
+    pub fn check(&self, day: u32, part: Part, input: &str, actual: &Answer) -> Verdict {
+        match self.expected(day, part, input) {
+            Some(expected) if expected == actual => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Missing,
+        }
+    }
+
+    /// Records an accepted answer, replacing any previous one
+    pub fn accept(&mut self, day: u32, part: Part, input: &str, answer: Answer) {
+        self.answers
+            .retain(|e| !(e.day == day && e.part == part && e.input == input));
+        self.answers.push(Entry {
+            day,
+            part,
+            input: input.to_owned(),
+            answer,
+        });
+        self.answers
+            .sort_by_key(|e| (e.day, e.part, e.input.clone()));
+    }
+
+    pub fn reject(
+        &mut self,
+        day: u32,
+        part: Part,
+        input: &str,
+        answer: Answer,
+        hint: Option<Hint>,
+    ) {
+        self.rejected.push(Rejected {
+            day,
+            part,
+            input: input.to_owned(),
+            answer,
+            hint,
+        });
+    }
+
+    /// Records that no answer can be sent for a part before `until`, replacing any earlier wait
+    pub fn wait_until(&mut self, day: u32, part: Part, until: SystemTime) {
+        self.wait.retain(|w| !(w.day == day && w.part == part));
+        self.wait.push(Wait {
+            day,
+            part,
+            until: epoch_secs(until).saturating_add(1),
+        });
+    }
+
+    /// How much longer to wait at `now` before sending an answer for a part, if at all
+    #[must_use]
+    pub fn waiting(&self, day: u32, part: Part, now: SystemTime) -> Option<Duration> {
+        let now = epoch_secs(now);
+        self.wait
+            .iter()
+            .find(|w| w.day == day && w.part == part && w.until > now)
+            .map(|w| Duration::from_secs(w.until - now))
+    }
+
+    /// Finds a rejection that rules out an answer, either the same answer or, for integers, one
+    /// that was too high or too low beyond it
+    #[must_use]
+    pub fn rejection(
+        &self,
+        day: u32,
+        part: Part,
+        input: &str,
+        answer: &Answer,
+    ) -> Option<&Rejected> {
+        self.rejected
+            .iter()
+            .filter(|r| r.day == day && r.part == part && r.input == input)
+            .find(|r| {
+                r.answer == *answer
+                    || match (&r.answer, answer, r.hint) {
+                        (Answer::Integer(r), Answer::Integer(a), Some(Hint::TooHigh)) => a >= r,
+                        (Answer::Integer(r), Answer::Integer(a), Some(Hint::TooLow)) => a <= r,
+                        _ => false,
+                    }
+            })
+    }
+}
+
+/// Whole seconds since the Unix epoch, rounded down
+fn epoch_secs(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+[[answer]]
+day = 1
+part = "a"
+input = "5e0c6a1d9b7f2e43"
+answer = "24000"
+"#;
+
+    #[test]
+    fn check_answers() -> Result<(), anyhow::Error> {
+        let store = AnswerStore::parse(ANSWERS)?;
+        assert_eq!(
+            store.check(1, Part::A, "5e0c6a1d9b7f2e43", &24000.into()),
+            Verdict::Pass
+        );
+        assert_eq!(
+            store.check(1, Part::A, "5e0c6a1d9b7f2e43", &1.into()),
+            Verdict::Fail {
+                expected: 24000.into()
+            }
+        );
+        assert_eq!(
+            store.check(1, Part::B, "5e0c6a1d9b7f2e43", &1.into()),
+            Verdict::Missing
+        );
+        assert_eq!(
+            store.check(1, Part::A, "0123456789abcdef", &1.into()),
+            Verdict::Missing
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejected_answers() -> Result<(), anyhow::Error> {
+        let mut store = AnswerStore::parse(ANSWERS)?;
+        store.reject(
+            1,
+            Part::B,
+            "5e0c6a1d9b7f2e43",
+            500.into(),
+            Some(Hint::TooHigh),
+        );
+        store.reject(
+            1,
+            Part::B,
+            "5e0c6a1d9b7f2e43",
+            100.into(),
+            Some(Hint::TooLow),
+        );
+        store.reject(1, Part::B, "5e0c6a1d9b7f2e43", 300.into(), None);
+
+        let rejected = |a: i32| {
+            store
+                .rejection(1, Part::B, "5e0c6a1d9b7f2e43", &a.into())
+                .is_some()
+        };
+        assert!(rejected(600));
+        assert!(rejected(50));
+        assert!(rejected(300));
+        assert!(!rejected(200));
+        assert!(store
+            .rejection(1, Part::A, "5e0c6a1d9b7f2e43", &600.into())
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn waits_per_part() -> Result<(), anyhow::Error> {
+        let mut store = AnswerStore::parse(ANSWERS)?;
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        store.wait_until(1, Part::B, now + Duration::from_mins(1));
+
+        assert_eq!(
+            store.waiting(1, Part::B, now),
+            Some(Duration::from_secs(61))
+        );
+        assert!(store.waiting(1, Part::A, now).is_none());
+        assert!(store
+            .waiting(1, Part::B, now + Duration::from_secs(61))
+            .is_none());
+
+        store.wait_until(1, Part::B, now);
+        assert_eq!(store.waiting(1, Part::B, now), Some(Duration::from_secs(1)));
+        Ok(())
+    }
+
+    #[test]
+    fn saves_and_reloads() -> Result<(), anyhow::Error> {
+        let path = std::env::temp_dir().join(format!("aoc-answers-{}.toml", std::process::id()));
+        let mut store = AnswerStore::parse(ANSWERS)?;
+        store.accept(
+            10,
+            Part::B,
+            "9a8b7c6d5e4f3a2b",
+            Answer::Image("#.\n.#\n".to_owned()),
+        );
+        store.reject(1, Part::B, "5e0c6a1d9b7f2e43", 5.into(), Some(Hint::TooLow));
+        store.wait_until(2, Part::A, SystemTime::now());
+
+        store.save(&path)?;
+        let reloaded = AnswerStore::load(&path);
+        fs::remove_file(&path)?;
+
+        assert_eq!(reloaded?, store);
+        Ok(())
+    }
+
+    #[test]
+    fn recorded_answers_are_as_the_store_writes_them() -> Result<(), anyhow::Error> {
+        let recorded = fs::read_to_string(DEFAULT_PATH)?;
+        let path = std::env::temp_dir().join(format!("aoc-recorded-{}.toml", std::process::id()));
+        AnswerStore::parse(&recorded)?.save(&path)?;
+        let written = fs::read_to_string(&path);
+        fs::remove_file(&path)?;
+
+        assert_eq!(written?, recorded);
+        Ok(())
+    }
+
+    #[test]
+    fn recorded_answers_are_for_the_inputs() -> Result<(), anyhow::Error> {
+        let store = AnswerStore::load(Path::new(DEFAULT_PATH))?;
+        let inputs = crate::Inputs::default();
+        for e in &store.answers {
+            let digest = crate::inputs::digest(&inputs.load(e.day)?);
+            assert_eq!(e.input, digest, "day{:02}{}", e.day, e.part);
+        }
+        Ok(())
+    }
+}
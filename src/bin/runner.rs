@@ -1,91 +0,0 @@
-#[allow(clippy::wildcard_imports)]
-use advent_of_code_2022::*;
-
-fn main() -> Result<(), anyhow::Error> {
-    println!(
-        "day17: {}, {}",
-        day17::solve_part_a()?,
-        day17::solve_part_b()?
-    );
-    println!(
-        "day16: {}, {}",
-        day16::solve_part_a()?,
-        day16::solve_part_b()?
-    );
-    println!(
-        "day15: {}, {}",
-        day15::solve_part_a()?,
-        day15::solve_part_b()?
-    );
-    println!(
-        "day14: {}, {}",
-        day14::solve_part_a()?,
-        day14::solve_part_b()?
-    );
-    println!(
-        "day13: {}, {}",
-        day13::solve_part_a()?,
-        day13::solve_part_b()?
-    );
-    println!(
-        "day12: {}, {}",
-        day12::solve_part_a()?,
-        day12::solve_part_b()?
-    );
-    println!(
-        "day11: {}, {}",
-        day11::solve_part_a()?,
-        day11::solve_part_b()?
-    );
-    println!(
-        "day10: {}, \n{}",
-        day10::solve_part_a()?,
-        day10::solve_part_b()?
-    );
-    println!(
-        "day09: {}, {}",
-        day09::solve_part_a()?,
-        day09::solve_part_b()?
-    );
-    println!(
-        "day08: {}, {}",
-        day08::solve_part_a(),
-        day08::solve_part_b()
-    );
-    println!(
-        "day07: {}, {}",
-        day07::solve_part_a()?,
-        day07::solve_part_b()?
-    );
-    println!(
-        "day06: {}, {}",
-        day06::solve_part_a()?,
-        day06::solve_part_b()?
-    );
-    println!(
-        "day05: {}, {}",
-        day05::solve_part_a()?,
-        day05::solve_part_b()?
-    );
-    println!(
-        "day04: {}, {}",
-        day04::solve_part_a()?,
-        day04::solve_part_b()?
-    );
-    println!(
-        "day03: {}, {}",
-        day03::solve_part_a()?,
-        day03::solve_part_b()?
-    );
-    println!(
-        "day02: {}, {}",
-        day02::solve_part_a()?,
-        day02::solve_part_b()?
-    );
-    println!(
-        "day01: {}, {}",
-        day01::solve_part_a()?,
-        day01::solve_part_b()?
-    );
-    Ok(())
-}
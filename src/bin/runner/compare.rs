@@ -0,0 +1,178 @@
+use std::time::{Duration, Instant};
+
+use advent_of_code_2022::{
+    cancel, inputs,
+    timing::{format_duration, Timings},
+    Answer, DynSolution, Part,
+};
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::RunArgs;
+
+/// One implementation of a part, with its first answer and how long it took over every repeat
+struct Compared {
+    implementation: &'static str,
+    answer: Result<Answer, anyhow::Error>,
+    times: Timings,
+}
+
+/// Runs every implementation of each part that has more than one against the same input, printing
+/// their answers and times relative to the default, and returning the number of parts whose
+/// implementations fail or disagree
+pub fn compare(args: &RunArgs, repeat: u32) -> Result<usize, anyhow::Error> {
+    cancel::set_budget(args.timeout);
+    let work = args
+        .solutions()?
+        .into_iter()
+        .map(|s| {
+            let parts = args
+                .parts()
+                .into_iter()
+                .filter(|&p| s.implementations(p).len() > 1)
+                .collect_vec();
+            (s, parts)
+        })
+        .filter(|(_, parts)| !parts.is_empty())
+        .collect_vec();
+    if work.is_empty() {
+        return Err(anyhow!(
+            "none of the chosen parts have more than one implementation"
+        ));
+    }
+
+    let mut failed = 0;
+    println!(
+        "day    part   {:14}  {:>16}  {:>9}  {:>8}",
+        "implementation", "answer", "time", "relative"
+    );
+    for (s, parts) in work {
+        let input = match args.input(s.day()) {
+            Ok(input) => inputs::normalise(&input).into_owned(),
+            Err(e) => {
+                eprintln!("day{:02}: {e:#}", s.day());
+                failed += parts.len();
+                continue;
+            }
+        };
+        for part in parts {
+            let compared = time_implementations(s, &input, part, repeat)?;
+            print_part(s.day(), part, &compared);
+            if !agree(&compared) {
+                failed += 1;
+            }
+        }
+    }
+    Ok(failed)
+}
+
+/// Solves a part from one parse of the input with each of its implementations in turn, `repeat`
+/// times over
+fn time_implementations(
+    s: &dyn DynSolution,
+    input: &str,
+    part: Part,
+    repeat: u32,
+) -> Result<Vec<Compared>, anyhow::Error> {
+    let mut compared = Vec::new();
+    s.with_implementations(input, &mut |solve| {
+        for implementation in s.implementations(part) {
+            let mut times = Timings::default();
+            let start = Instant::now();
+            let answer = solve(part, implementation);
+            times.push(start.elapsed());
+            let repeat = if answer.as_ref().is_err_and(cancel::timed_out) {
+                1
+            } else {
+                repeat
+            };
+            for _ in 1..repeat {
+                let start = Instant::now();
+                let _ = solve(part, implementation);
+                times.push(start.elapsed());
+            }
+            compared.push(Compared {
+                implementation,
+                answer,
+                times,
+            });
+        }
+    })?;
+    Ok(compared)
+}
+
+/// Whether every implementation gave the same answer, without failing
+fn agree(compared: &[Compared]) -> bool {
+    compared.iter().all(|c| c.answer.is_ok())
+        && compared.iter().map(|c| c.answer.as_ref().ok()).all_equal()
+}
+
+/// Prints a row per implementation, flagging the part if they don't agree
+fn print_part(day: u32, part: Part, compared: &[Compared]) {
+    let baseline = compared.first().map(|c| c.times.median());
+    for c in compared {
+        let answer = match &c.answer {
+            Ok(answer) => answer.to_string(),
+            Err(e) => {
+                eprintln!("day{day:02}{part} {}: {e:#}", c.implementation);
+                crate::failure(e).to_owned()
+            }
+        };
+        println!(
+            "day{day:02}  {:5}  {:14}  {answer:>16}  {:>9}  {:>8}",
+            part.to_string(),
+            c.implementation,
+            format_duration(c.times.median()),
+            baseline.map_or_else(String::new, |b| relative(c.times.median(), b)),
+        );
+    }
+    if !agree(compared) {
+        println!("day{day:02}  {:5}  MISMATCH", part.to_string());
+    }
+}
+
+/// How many times longer a step took than the baseline
+fn relative(time: Duration, baseline: Duration) -> String {
+    if baseline.is_zero() {
+        "-".to_owned()
+    } else {
+        format!("{:.2}x", time.as_secs_f64() / baseline.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compared(answer: Result<Answer, anyhow::Error>) -> Compared {
+        Compared {
+            implementation: "default",
+            answer,
+            times: Timings::default(),
+        }
+    }
+
+    #[test]
+    fn agreeing() {
+        assert!(agree(&[
+            compared(Ok(Answer::from(1))),
+            compared(Ok(Answer::from(1)))
+        ]));
+        assert!(!agree(&[
+            compared(Ok(Answer::from(1))),
+            compared(Ok(Answer::from(2)))
+        ]));
+        assert!(!agree(&[
+            compared(Err(anyhow!("no route"))),
+            compared(Err(anyhow!("no route")))
+        ]));
+    }
+
+    #[test]
+    fn relative_times() {
+        let ms = Duration::from_millis;
+        assert_eq!(relative(ms(3), ms(2)), "1.50x");
+        assert_eq!(relative(ms(2), ms(2)), "1.00x");
+        assert_eq!(relative(ms(2), Duration::ZERO), "-");
+    }
+}
@@ -0,0 +1,69 @@
+use std::{fs::File, path::Path};
+
+use anyhow::Context;
+use tracing::level_filters::LevelFilter;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::{
+    fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, Layer,
+};
+
+/// The most detailed logs to print for how many times `-v` was given
+pub const fn level(verbose: u8) -> LevelFilter {
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Logs to stderr if `-v` was given, and writes every span to a Chrome trace if a path was given.
+/// The trace is only complete once the returned guard is dropped.
+pub fn init(verbose: u8, trace: Option<&Path>) -> Result<Option<FlushGuard>, anyhow::Error> {
+    if verbose == 0 && trace.is_none() {
+        return Ok(None);
+    }
+
+    let logs = (verbose > 0).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_filter(level(verbose))
+    });
+    let (chrome, guard) = match trace {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("Couldn't create trace file {}", path.display()))?;
+            let (layer, guard) = ChromeLayerBuilder::new()
+                .writer(file)
+                .include_args(true)
+                .build();
+            // Spans are all at info, so a trace always has every step in it
+            (
+                Some(layer.with_filter(level(verbose).max(LevelFilter::INFO))),
+                Some(guard),
+            )
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(logs)
+        .with(chrome)
+        .try_init()?;
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn more_verbose_logs_more() {
+        assert_eq!(level(0), LevelFilter::WARN);
+        assert_eq!(level(1), LevelFilter::INFO);
+        assert_eq!(level(2), LevelFilter::DEBUG);
+        assert_eq!(level(3), LevelFilter::TRACE);
+        assert_eq!(level(9), LevelFilter::TRACE);
+    }
+}
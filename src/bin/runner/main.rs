@@ -0,0 +1,883 @@
+use std::{
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime},
+};
+
+use advent_of_code_2022::{
+    allocations::Allocations,
+    answers::{self, AnswerStore, Verdict},
+    cancel,
+    client::{self, Client, Fetched, Outcome},
+    inputs,
+    timing::Timings,
+    Answer, DynSolution, Inputs, Part, SOLUTIONS,
+};
+use anyhow::anyhow;
+use clap::{ArgAction, Args, Parser, Subcommand};
+use itertools::Itertools;
+use rayon::{prelude::*, ThreadPoolBuilder};
+
+use self::{output::Format, perf::HistoryArgs};
+
+mod compare;
+mod logging;
+mod output;
+mod perf;
+mod report;
+mod scaffold;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: advent_of_code_2022::allocations::CountingAllocator =
+    advent_of_code_2022::allocations::CountingAllocator;
+
+/// Runs the Advent of Code 2022 solutions
+#[derive(Parser, Debug)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+
+    /// List the available days and exit
+    #[arg(long)]
+    list: bool,
+
+    /// How to print the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Number of days and parts to run at once, 0 for one per CPU
+    #[arg(long, short, default_value_t = 1)]
+    jobs: usize,
+
+    /// Run each day this many times, reporting the min and median times
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    repeat: u32,
+
+    /// Report how many allocations each step makes and the most memory it has in use at once,
+    /// which needs the runner built with `--features count-allocations`
+    #[arg(long)]
+    allocations: bool,
+
+    /// Add the median time of every part to the benchmark history, for `perf-diff` to compare
+    /// against. Needs a clean working tree, and the puzzle inputs run one at a time
+    #[arg(long, conflicts_with_all = ["input", "jobs"])]
+    record: bool,
+
+    #[command(flatten)]
+    history: HistoryArgs,
+
+    /// Log what the solutions are doing to stderr, more with each `-v`
+    #[arg(long, short, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    /// Write every step as a Chrome trace, to open in `chrome://tracing` or Perfetto
+    #[arg(long, global = true)]
+    trace: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Check the answers against the accepted answers store
+    Verify {
+        #[command(flatten)]
+        run: RunArgs,
+
+        /// File holding the accepted answers
+        #[arg(long, default_value = answers::DEFAULT_PATH)]
+        answers: PathBuf,
+    },
+    /// Run every implementation of the parts that have more than one, checking they give the same
+    /// answer and comparing how long they take
+    Compare {
+        #[command(flatten)]
+        run: RunArgs,
+
+        /// Run each implementation this many times, comparing the median times
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+    },
+    /// Run the days and compare their median times against those recorded for an earlier commit,
+    /// flagging any part that has slowed down by more than a threshold
+    PerfDiff {
+        #[command(flatten)]
+        run: RunArgs,
+
+        /// Commit to compare against, or any prefix of its hash. Defaults to the one most recently
+        /// recorded on this machine
+        #[arg(long)]
+        baseline: Option<String>,
+
+        /// How many percent slower a part can get before it is flagged, either for every day (5)
+        /// or for one day (16=25). Defaults to 10 for every day
+        #[arg(long)]
+        threshold: Vec<perf::Threshold>,
+
+        /// Run each day this many times, comparing the median times
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+
+        /// Also add this run's times to the history, which needs a clean working tree and the
+        /// puzzle inputs
+        #[arg(long, conflicts_with = "input")]
+        record: bool,
+
+        #[command(flatten)]
+        history: HistoryArgs,
+    },
+    /// Run the days and write a page of their answers, whether they are right, how long they took
+    /// and the visualisations of the days that have one
+    Report {
+        #[command(flatten)]
+        run: RunArgs,
+
+        /// Directory to write the page and its visualisations to
+        #[arg(long, value_name = "DIR")]
+        html: PathBuf,
+
+        /// File holding the accepted answers
+        #[arg(long, default_value = answers::DEFAULT_PATH)]
+        answers: PathBuf,
+
+        /// Run each day this many times, reporting the median times
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+    },
+    /// Download puzzle inputs into the inputs directory, skipping any already there
+    Fetch {
+        /// Days to download, either a single day (15) or an inclusive range (10-17)
+        #[arg(required = true)]
+        days: Vec<DayRange>,
+
+        #[command(flatten)]
+        site: SiteArgs,
+
+        /// Directory to save the inputNN.txt puzzle inputs in
+        #[arg(long, env = "AOC_INPUTS", default_value = inputs::DEFAULT_DIR)]
+        inputs_dir: PathBuf,
+    },
+    /// Solve one part of a day and submit the answer, recording the outcome in the answers store
+    Submit {
+        day: u32,
+
+        part: Part,
+
+        #[command(flatten)]
+        site: SiteArgs,
+
+        /// Directory containing the inputNN.txt puzzle inputs
+        #[arg(long, env = "AOC_INPUTS", default_value = inputs::DEFAULT_DIR)]
+        inputs_dir: PathBuf,
+
+        /// File holding the accepted answers
+        #[arg(long, default_value = answers::DEFAULT_PATH)]
+        answers: PathBuf,
+    },
+    /// Generate the module and resource files for a new day and register it with the library
+    New {
+        day: u32,
+
+        /// The puzzle's title
+        #[arg(long, default_value = "")]
+        title: String,
+
+        /// Root of the source tree to add the day to
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+    /// Print a made up puzzle input of any size, for measuring how a day scales
+    Gen {
+        day: u32,
+
+        /// How big to make the input, such as the number of lines, see each day for what it counts
+        /// and `--list` for the sizes it supports
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        size: u64,
+
+        /// Seed for the random choices, the same seed and size always give the same input
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+
+#[derive(Args, Debug)]
+struct SiteArgs {
+    /// Address of the Advent of Code site
+    #[arg(long, env = "AOC_BASE_URL", default_value = client::DEFAULT_BASE_URL)]
+    base_url: String,
+
+    /// File holding the session cookie, used when `AOC_SESSION` isn't set
+    #[arg(long)]
+    session_file: Option<PathBuf>,
+}
+
+impl SiteArgs {
+    fn client(&self) -> Result<Client, anyhow::Error> {
+        let file = self
+            .session_file
+            .clone()
+            .unwrap_or_else(client::default_session_file);
+        Ok(Client::new(&self.base_url, client::session(&file)?))
+    }
+}
+
+#[derive(Args, Debug)]
+struct RunArgs {
+    /// Days to run, either a single day (15) or an inclusive range (10-17)
+    days: Vec<DayRange>,
+
+    /// Run every day, the default when no days are given
+    #[arg(long, conflicts_with = "days")]
+    all: bool,
+
+    /// Only run one part of each day
+    #[arg(long, short)]
+    part: Option<Part>,
+
+    /// Read the input from this file, or stdin for '-', instead of the inputs directory
+    #[arg(long, short)]
+    input: Option<PathBuf>,
+
+    /// Directory containing the inputNN.txt puzzle inputs
+    #[arg(long, env = "AOC_INPUTS", default_value = inputs::DEFAULT_DIR)]
+    inputs_dir: PathBuf,
+
+    /// Seconds each part may run for before it is asked to give up and reported as timed out
+    #[arg(long, value_parser = parse_seconds)]
+    timeout: Option<Duration>,
+}
+
+fn parse_seconds(s: &str) -> Result<Duration, anyhow::Error> {
+    let seconds = Duration::try_from_secs_f64(s.parse()?)?;
+    if seconds.is_zero() {
+        Err(anyhow!("the timeout needs to be more than zero"))
+    } else {
+        Ok(seconds)
+    }
+}
+
+impl RunArgs {
+    fn solutions(&self) -> Result<Vec<&'static dyn DynSolution>, anyhow::Error> {
+        let solutions = select(if self.all { &[] } else { &self.days })?;
+        if self.input.is_some() && solutions.len() != 1 {
+            return Err(anyhow!(
+                "--input can only be used when running a single day"
+            ));
+        }
+        Ok(solutions)
+    }
+
+    fn parts(&self) -> Vec<Part> {
+        self.part.map_or_else(|| Part::ALL.to_vec(), |p| vec![p])
+    }
+
+    /// The input a day will be run against
+    fn input(&self, day: u32) -> Result<String, anyhow::Error> {
+        self.input
+            .as_deref()
+            .map_or_else(|| Inputs::new(&self.inputs_dir).load(day), inputs::read)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DayRange(RangeInclusive<u32>);
+
+impl FromStr for DayRange {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let range = if let Some((start, end)) = s.split_once('-') {
+            start.trim().parse()?..=end.trim().parse()?
+        } else {
+            let day = s.trim().parse()?;
+            day..=day
+        };
+
+        if range.is_empty() {
+            Err(anyhow!("empty day range '{s}'"))
+        } else {
+            Ok(Self(range))
+        }
+    }
+}
+
+fn select(days: &[DayRange]) -> Result<Vec<&'static dyn DynSolution>, anyhow::Error> {
+    if days.is_empty() {
+        return Ok(SOLUTIONS.to_vec());
+    }
+
+    if let Some(missing) = days
+        .iter()
+        .flat_map(|r| r.0.clone())
+        .find(|d| SOLUTIONS.iter().all(|s| s.day() != *d))
+    {
+        return Err(anyhow!("day {missing} has no solution"));
+    }
+
+    Ok(SOLUTIONS
+        .iter()
+        .copied()
+        .filter(|s| days.iter().any(|r| r.0.contains(&s.day())))
+        .collect())
+}
+
+/// Solves the requested parts of a day, reporting any errors as they happen
+fn solve(
+    s: &dyn DynSolution,
+    input: &Result<String, anyhow::Error>,
+    parts: &[Part],
+) -> Vec<(Part, Result<Answer, anyhow::Error>)> {
+    let input = match input {
+        Ok(i) => i,
+        Err(e) => {
+            eprintln!("day{:02}: {e:#}", s.day());
+            return parts.iter().map(|&p| (p, Err(anyhow!("{e:#}")))).collect();
+        }
+    };
+
+    parts
+        .iter()
+        .map(|&part| {
+            let answer = s.solve(input, part);
+            if let Err(e) = &answer {
+                eprintln!("day{:02}{part}: {e:#}", s.day());
+            }
+            (part, answer)
+        })
+        .collect()
+}
+
+/// What to show in place of the answer to a part that failed
+fn failure(e: &anyhow::Error) -> &'static str {
+    if cancel::timed_out(e) {
+        "TIMEOUT"
+    } else {
+        "ERROR"
+    }
+}
+
+/// A day's answers along with the time taken for every step over each repeat, and what the first
+/// run of each step allocated
+pub struct Measured {
+    pub parse: Timings,
+    pub parse_allocations: Allocations,
+    pub parts: Vec<MeasuredPart>,
+}
+
+pub struct MeasuredPart {
+    pub part: Part,
+    pub answer: Result<Answer, anyhow::Error>,
+    pub times: Timings,
+    pub allocations: Allocations,
+}
+
+pub struct DayResult {
+    pub day: u32,
+    pub parts: Vec<Part>,
+    /// The input the day ran against, if it could be read
+    pub input: Option<String>,
+    pub measured: Result<Measured, anyhow::Error>,
+}
+
+impl DayResult {
+    fn failed(&self) -> bool {
+        self.measured
+            .as_ref()
+            .map_or(true, |m| m.parts.iter().any(|p| p.answer.is_err()))
+    }
+}
+
+fn measure(
+    s: &dyn DynSolution,
+    input: &str,
+    parts: &[Part],
+    repeat: u32,
+) -> Result<Measured, anyhow::Error> {
+    let first = s.run(input, parts)?;
+    let mut parse = Timings::default();
+    parse.push(first.parse);
+
+    let mut parts = first
+        .parts
+        .into_iter()
+        .map(|p| {
+            let mut times = Timings::default();
+            times.push(p.duration);
+            MeasuredPart {
+                part: p.part,
+                answer: p.answer,
+                times,
+                allocations: p.allocations,
+            }
+        })
+        .collect_vec();
+
+    // Running out of time once is enough, so only the parts that finished are run again
+    let mut again = parts
+        .iter_mut()
+        .filter(|p| !p.answer.as_ref().is_err_and(cancel::timed_out))
+        .collect_vec();
+    for _ in 1..repeat {
+        let run = s.run(input, &again.iter().map(|p| p.part).collect_vec())?;
+        parse.push(run.parse);
+        for (measured, p) in again.iter_mut().zip(run.parts) {
+            measured.times.push(p.duration);
+        }
+    }
+
+    Ok(Measured {
+        parse,
+        parse_allocations: first.parse_allocations,
+        parts,
+    })
+}
+
+/// Combines the measurements of a day that was split up to run its parts in parallel. Each part
+/// parsed the input itself, so the parse times add up.
+fn merge(chunks: Vec<Result<Measured, anyhow::Error>>) -> Result<Measured, anyhow::Error> {
+    let mut merged: Option<Measured> = None;
+    for m in chunks {
+        let m = m?;
+        match &mut merged {
+            Some(merged) => {
+                merged.parse.accumulate(&m.parse);
+                merged.parts.extend(m.parts);
+            }
+            None => merged = Some(m),
+        }
+    }
+    merged.ok_or_else(|| anyhow!("no parts were run"))
+}
+
+/// Runs and times each day, reporting any errors as they happen, and returns the results along
+/// with how long they took altogether
+fn measure_days(
+    args: &RunArgs,
+    repeat: u32,
+    jobs: usize,
+) -> Result<(Vec<DayResult>, Duration), anyhow::Error> {
+    cancel::set_budget(args.timeout);
+    let parts = args.parts();
+    let days = args
+        .solutions()?
+        .into_iter()
+        .map(|s| (s, args.input(s.day())))
+        .collect_vec();
+
+    // Sequential runs parse each input once for all parts, parallel runs give each part its own job
+    let chunks = if jobs == 1 {
+        vec![parts.clone()]
+    } else {
+        parts.iter().map(|&p| vec![p]).collect_vec()
+    };
+    let work = days
+        .iter()
+        .flat_map(|(s, input)| chunks.iter().map(move |c| (*s, input, c.as_slice())))
+        .collect_vec();
+
+    let measure_chunk = |&(s, input, parts): &(&dyn DynSolution, &Result<String, _>, &[Part])| {
+        input
+            .as_ref()
+            .map_err(|e| anyhow!("{e:#}"))
+            .and_then(|input| measure(s, input, parts, repeat))
+    };
+
+    let start = Instant::now();
+    let measured = if jobs == 1 {
+        work.iter().map(measure_chunk).collect_vec()
+    } else {
+        ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build()?
+            .install(|| work.par_iter().map(measure_chunk).collect())
+    };
+    let wall = start.elapsed();
+
+    let mut measured = measured.into_iter();
+    let results = days
+        .into_iter()
+        .map(|(s, input)| DayResult {
+            day: s.day(),
+            parts: parts.clone(),
+            input: input.ok(),
+            measured: merge(measured.by_ref().take(chunks.len()).collect()),
+        })
+        .collect_vec();
+
+    for r in &results {
+        match &r.measured {
+            Ok(m) => {
+                for p in &m.parts {
+                    if let Err(e) = &p.answer {
+                        eprintln!("day{:02}{}: {e:#}", r.day, p.part);
+                    }
+                }
+            }
+            Err(e) => eprintln!("day{:02}: {e:#}", r.day),
+        }
+    }
+
+    Ok((results, wall))
+}
+
+/// Runs and times each day, printing the results in the chosen format and recording them in the
+/// history if asked, and returning the number of days with a failing part
+fn run(
+    args: &RunArgs,
+    repeat: u32,
+    jobs: usize,
+    format: Format,
+    allocations: bool,
+    record: Option<&HistoryArgs>,
+) -> Result<usize, anyhow::Error> {
+    if allocations && !cfg!(feature = "count-allocations") {
+        return Err(anyhow!(
+            "--allocations needs the runner built with --features count-allocations"
+        ));
+    }
+    if allocations && jobs != 1 {
+        return Err(anyhow!(
+            "--allocations counts every thread's allocations together, so needs --jobs 1"
+        ));
+    }
+
+    let recorder = record
+        .map(|args| perf::Recorder::new(args, Path::new(".")))
+        .transpose()?;
+
+    let (results, wall) = measure_days(args, repeat, jobs)?;
+    output::print(format, &results, repeat, wall, allocations)?;
+    if let Some(recorder) = recorder {
+        recorder.record(&results)?;
+    }
+
+    Ok(results.iter().filter(|r| r.failed()).count())
+}
+
+/// Checks each answer against the store, returning the number of days with a wrong or failing part
+fn verify(args: &RunArgs, answers: &AnswerStore) -> Result<usize, anyhow::Error> {
+    cancel::set_budget(args.timeout);
+    let parts = args.parts();
+    let mut failed = 0;
+
+    for s in args.solutions()? {
+        let input = args.input(s.day());
+        let digest = input.as_deref().map(inputs::digest).unwrap_or_default();
+        let mut day_failed = false;
+
+        for (part, answer) in solve(s, &input, &parts) {
+            let label = format!("day{:02}{part}", s.day());
+            let answer = match answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    println!("{label}  {}", failure(&e));
+                    day_failed = true;
+                    continue;
+                }
+            };
+
+            match answers.check(s.day(), part, &digest, &answer) {
+                Verdict::Fail { expected } => {
+                    println!("{label}  FAIL  expected {expected}, got {answer}");
+                    day_failed = true;
+                }
+                v => println!("{label}  {v}"),
+            }
+        }
+
+        if day_failed {
+            failed += 1;
+        }
+    }
+
+    Ok(failed)
+}
+
+/// Downloads each day's input unless it is already cached, returning the number that failed
+fn fetch(days: &[DayRange], site: &SiteArgs, inputs: &Inputs) -> Result<usize, anyhow::Error> {
+    let days = days.iter().flat_map(|r| r.0.clone()).unique().collect_vec();
+    if let Some(day) = days.iter().find(|d| !(1..=25).contains(*d)) {
+        return Err(anyhow!("there is no day {day}"));
+    }
+
+    let mut client = None;
+    let mut failed = 0;
+    for day in days {
+        if let Some(path) = inputs.cached(day) {
+            println!("day{day:02}  cached      {}", path.display());
+            continue;
+        }
+
+        // Only look for a session once there is something to download
+        let client = match &mut client {
+            Some(client) => client,
+            None => client.insert(site.client()?),
+        };
+        match client.fetch(inputs, day) {
+            Ok(Fetched::Cached(path) | Fetched::Downloaded(path)) => {
+                println!("day{day:02}  downloaded  {}", path.display());
+            }
+            Err(e) => {
+                eprintln!("day{day:02}: {e:#}");
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+/// Submits a freshly computed answer unless the store already knows whether it is right, returning
+/// 1 unless it is accepted
+fn submit(
+    day: u32,
+    part: Part,
+    site: &SiteArgs,
+    inputs: &Inputs,
+    answers_path: &Path,
+) -> Result<usize, anyhow::Error> {
+    let s = select(&[DayRange(day..=day)])?[0];
+    let mut answers = AnswerStore::load(answers_path)?;
+    let input = inputs.load(day)?;
+    let digest = inputs::digest(&input);
+    let answer = s.solve(&input, part)?;
+    let label = format!("day{day:02}{part}");
+
+    if let Answer::Image(_) = answer {
+        return Err(anyhow!(
+            "{label} is an image, read it and submit it by hand:\n{answer}"
+        ));
+    }
+    match answers.check(day, part, &digest, &answer) {
+        Verdict::Pass => {
+            println!("{label}  {answer} was already accepted");
+            return Ok(0);
+        }
+        Verdict::Fail { expected } => {
+            return Err(anyhow!(
+                "{label} already has an accepted answer {expected}, not submitting {answer}"
+            ));
+        }
+        Verdict::Missing => {}
+    }
+    if let Some(rejected) = answers.rejection(day, part, &digest, &answer) {
+        let hint = rejected.hint.map(|h| format!(", {h}")).unwrap_or_default();
+        return Err(anyhow!(
+            "{label} not submitting {answer}, {} was already rejected{hint}",
+            rejected.answer
+        ));
+    }
+
+    if let Some(wait) = answers.waiting(day, part, SystemTime::now()) {
+        return Err(anyhow!(
+            "{label} not submitting {answer}, the site asked to wait another {}s",
+            wait.as_secs()
+        ));
+    }
+
+    let outcome = site.client()?.submit(day, part, &answer)?;
+    println!("{label}  {answer}  {outcome}");
+    match outcome {
+        Outcome::Correct => answers.accept(day, part, &digest, answer),
+        Outcome::Wrong(hint) => answers.reject(day, part, &digest, answer, hint),
+        Outcome::Wait(wait) => answers.wait_until(day, part, SystemTime::now() + wait),
+        Outcome::AlreadySolved => return Ok(1),
+    }
+    answers.save(answers_path)?;
+
+    Ok(usize::from(outcome != Outcome::Correct))
+}
+
+/// Prints a generated input for a day
+fn generate(day: u32, size: u64, seed: u64) -> Result<usize, anyhow::Error> {
+    let s = select(&[DayRange(day..=day)])?[0];
+    println!("{}", s.generate(usize::try_from(size)?, seed)?);
+    Ok(0)
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let _trace = match logging::init(cli.verbose, cli.trace.as_deref()) {
+        Ok(guard) => guard,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            return ExitCode::from(2);
+        }
+    };
+
+    if cli.list {
+        for s in SOLUTIONS {
+            let sizes = s.generate_sizes();
+            println!(
+                "day{:02}: {} (generates sizes {} to {})",
+                s.day(),
+                s.title(),
+                sizes.start(),
+                sizes.end()
+            );
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let result = match &cli.command {
+        None => run(
+            &cli.run,
+            cli.repeat,
+            cli.jobs,
+            cli.format,
+            cli.allocations,
+            cli.record.then_some(&cli.history),
+        ),
+        Some(Command::Verify { run, answers }) => {
+            AnswerStore::load(answers).and_then(|answers| verify(run, &answers))
+        }
+        Some(Command::Compare { run, repeat }) => compare::compare(run, *repeat),
+        Some(Command::PerfDiff {
+            run,
+            baseline,
+            threshold,
+            repeat,
+            record,
+            history,
+        }) => perf::perf_diff(
+            run,
+            *repeat,
+            baseline.as_deref(),
+            threshold,
+            history,
+            *record,
+        ),
+        Some(Command::Report {
+            run,
+            html,
+            answers,
+            repeat,
+        }) => AnswerStore::load(answers)
+            .and_then(|answers| report::report(run, *repeat, &answers, html)),
+        Some(Command::Fetch {
+            days,
+            site,
+            inputs_dir,
+        }) => fetch(days, site, &Inputs::new(inputs_dir)),
+        Some(Command::Submit {
+            day,
+            part,
+            site,
+            inputs_dir,
+            answers,
+        }) => submit(*day, *part, site, &Inputs::new(inputs_dir), answers),
+        Some(Command::Gen { day, size, seed }) => generate(*day, *size, *seed),
+        Some(Command::New { day, title, root }) => {
+            scaffold::new_day(root, *day, title).map(|touched| {
+                for file in touched {
+                    println!("{file}");
+                }
+                0
+            })
+        }
+    };
+
+    match result {
+        Ok(0) => ExitCode::SUCCESS,
+        Ok(_) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_ranges() -> Result<(), anyhow::Error> {
+        assert_eq!("15".parse::<DayRange>()?, DayRange(15..=15));
+        assert_eq!("10-17".parse::<DayRange>()?, DayRange(10..=17));
+        assert!("17-10".parse::<DayRange>().is_err());
+        assert!("x".parse::<DayRange>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn select_days() -> Result<(), anyhow::Error> {
+        let days = select(&["3".parse()?, "5-6".parse()?])?;
+        assert_eq!(days.iter().map(|s| s.day()).collect_vec(), [3, 5, 6]);
+        assert!(select(&["99".parse()?]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn merges_parallel_parts() -> Result<(), anyhow::Error> {
+        let chunk = |part| Measured {
+            parse: {
+                let mut parse = Timings::default();
+                parse.push(Duration::from_millis(2));
+                parse
+            },
+            parse_allocations: Allocations::default(),
+            parts: vec![MeasuredPart {
+                part,
+                answer: Ok(Answer::from(1)),
+                times: Timings::default(),
+                allocations: Allocations::default(),
+            }],
+        };
+        let merged = merge(vec![Ok(chunk(Part::A)), Ok(chunk(Part::B))])?;
+        assert_eq!(merged.parts.iter().map(|p| p.part).collect_vec(), Part::ALL);
+        assert_eq!(merged.parse.median(), Duration::from_millis(4));
+        assert!(merge(vec![Ok(chunk(Part::A)), Err(anyhow!("bad input"))]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn cli_parses() {
+        assert!(Cli::try_parse_from(["runner", "16", "--part", "b"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "verify", "10-17"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "--all", "15"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "9", "--allocations"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "compare", "12", "--repeat", "5"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "15", "--timeout", "2.5"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "--record", "--machine", "laptop"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "--record", "--jobs", "4"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "--record", "-i", "-"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "perf-diff", "--record", "-i", "in.txt"]).is_err());
+        assert!(Cli::try_parse_from([
+            "runner",
+            "perf-diff",
+            "10-17",
+            "--baseline",
+            "95a5820",
+            "--threshold",
+            "5",
+            "--threshold",
+            "16=25"
+        ])
+        .is_ok());
+        assert!(Cli::try_parse_from(["runner", "perf-diff", "--threshold", "16=x"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "verify", "--timeout", "0"]).is_err());
+        assert!(
+            Cli::try_parse_from(["runner", "report", "--html", "out/", "--repeat", "3"]).is_ok()
+        );
+        assert!(Cli::try_parse_from(["runner", "report", "10"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "fetch", "18-25"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "fetch"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "submit", "18", "b"]).is_ok());
+        assert!(
+            Cli::try_parse_from(["runner", "new", "18", "--title", "Boiling Boulders"]).is_ok()
+        );
+        assert!(
+            Cli::try_parse_from(["runner", "gen", "14", "--size", "500", "--seed", "7"]).is_ok()
+        );
+        assert!(Cli::try_parse_from(["runner", "gen", "14", "--size", "0"]).is_err());
+        assert!(Cli::try_parse_from(["runner", "17", "-vv", "--trace", "out.json"]).is_ok());
+        assert!(Cli::try_parse_from(["runner", "verify", "-v"]).is_ok());
+    }
+}
@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use advent_of_code_2022::{
+    allocations::{format_bytes, Allocations},
+    timing::format_duration,
+    Answer, Part,
+};
+use serde::Serialize;
+
+use crate::DayResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+pub fn print(
+    format: Format,
+    results: &[DayResult],
+    repeat: u32,
+    wall: Duration,
+    allocations: bool,
+) -> Result<(), anyhow::Error> {
+    match format {
+        Format::Text => print_table(results, repeat, wall, allocations),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&records(results, allocations))?
+        ),
+        Format::Csv => print!("{}", to_csv(&records(results, allocations))),
+    }
+    Ok(())
+}
+
+/// One part of one day, flattened for other tools to consume
+#[derive(Debug, Serialize)]
+struct Record {
+    day: u32,
+    part: Part,
+    answer: Option<String>,
+    answer_type: Option<&'static str>,
+    /// The picture the letters of an answer were read from, left out of CSV which only has room
+    /// for the letters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    image: Option<String>,
+    parse_ns: Option<u64>,
+    duration_ns: Option<u64>,
+    error: Option<String>,
+    parse_allocations: Option<u64>,
+    parse_peak_bytes: Option<usize>,
+    allocations: Option<u64>,
+    peak_bytes: Option<usize>,
+}
+
+/// Flattens the results, only including the allocations if they were counted
+fn records(results: &[DayResult], allocations: bool) -> Vec<Record> {
+    let nanos = |d: Duration| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX);
+    let counted = |a: Allocations| allocations.then_some(a);
+
+    results
+        .iter()
+        .flat_map(|r| match &r.measured {
+            Ok(m) => m
+                .parts
+                .iter()
+                .map(|p| Record {
+                    day: r.day,
+                    part: p.part,
+                    answer: p.answer.as_ref().ok().map(ToString::to_string),
+                    answer_type: p.answer.as_ref().ok().map(Answer::kind),
+                    image: match &p.answer {
+                        Ok(a @ Answer::Letters { .. }) => a.image().map(str::to_owned),
+                        _ => None,
+                    },
+                    parse_ns: Some(nanos(m.parse.median())),
+                    duration_ns: Some(nanos(p.times.median())),
+                    error: p.answer.as_ref().err().map(|e| format!("{e:#}")),
+                    parse_allocations: counted(m.parse_allocations).map(|a| a.count),
+                    parse_peak_bytes: counted(m.parse_allocations).map(|a| a.peak_bytes),
+                    allocations: counted(p.allocations).map(|a| a.count),
+                    peak_bytes: counted(p.allocations).map(|a| a.peak_bytes),
+                })
+                .collect(),
+            Err(e) => r
+                .parts
+                .iter()
+                .map(|part| Record {
+                    day: r.day,
+                    part: *part,
+                    answer: None,
+                    answer_type: None,
+                    image: None,
+                    parse_ns: None,
+                    duration_ns: None,
+                    error: Some(format!("{e:#}")),
+                    parse_allocations: None,
+                    parse_peak_bytes: None,
+                    allocations: None,
+                    peak_bytes: None,
+                })
+                .collect::<Vec<_>>(),
+        })
+        .collect()
+}
+
+fn to_csv(records: &[Record]) -> String {
+    fn field(value: Option<&str>) -> String {
+        match value {
+            Some(v) if v.contains([',', '"', '\n', '\r']) => {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            }
+            Some(v) => v.to_owned(),
+            None => String::new(),
+        }
+    }
+
+    let mut csv = "day,part,answer,answer_type,parse_ns,duration_ns,error,\
+                   parse_allocations,parse_peak_bytes,allocations,peak_bytes\n"
+        .to_owned();
+    for r in records {
+        let row = [
+            Some(r.day.to_string()),
+            Some(r.part.to_string()),
+            r.answer.clone(),
+            r.answer_type.map(str::to_owned),
+            r.parse_ns.map(|n| n.to_string()),
+            r.duration_ns.map(|n| n.to_string()),
+            r.error.clone(),
+            r.parse_allocations.map(|n| n.to_string()),
+            r.parse_peak_bytes.map(|n| n.to_string()),
+            r.allocations.map(|n| n.to_string()),
+            r.peak_bytes.map(|n| n.to_string()),
+        ];
+        csv.push_str(
+            &row.iter()
+                .map(|v| field(v.as_deref()))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push('\n');
+    }
+    csv
+}
+
+struct Row {
+    day: u32,
+    step: String,
+    answer: String,
+    image: Option<String>,
+    min: Option<Duration>,
+    median: Option<Duration>,
+    allocations: Option<Allocations>,
+}
+
+/// Prints a row per step, then the summed time of every step and how long the whole run took
+fn print_table(results: &[DayResult], repeat: u32, wall: Duration, allocations: bool) {
+    let mut rows = Vec::new();
+    let mut total = Duration::ZERO;
+
+    for r in results {
+        let Ok(m) = &r.measured else {
+            rows.push(Row {
+                day: r.day,
+                step: "parse".to_owned(),
+                answer: "ERROR".to_owned(),
+                image: None,
+                median: None,
+                min: None,
+                allocations: None,
+            });
+            continue;
+        };
+
+        total += m.parse.median();
+        rows.push(Row {
+            day: r.day,
+            step: "parse".to_owned(),
+            answer: String::new(),
+            image: None,
+            median: Some(m.parse.median()),
+            min: Some(m.parse.min()),
+            allocations: Some(m.parse_allocations),
+        });
+
+        for p in &m.parts {
+            total += p.times.median();
+            rows.push(Row {
+                day: r.day,
+                step: p.part.to_string(),
+                answer: match &p.answer {
+                    Ok(Answer::Image(_)) => String::new(),
+                    Ok(answer) => answer.to_string(),
+                    Err(e) => crate::failure(e).to_owned(),
+                },
+                image: p
+                    .answer
+                    .as_ref()
+                    .ok()
+                    .and_then(Answer::image)
+                    .map(str::to_owned),
+                median: Some(p.times.median()),
+                min: Some(p.times.min()),
+                allocations: Some(p.allocations),
+            });
+        }
+    }
+
+    let width = rows
+        .iter()
+        .map(|r| r.answer.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("answer".len());
+
+    let allocations_header = if allocations {
+        format!("  {:>9}  {:>9}", "allocs", "peak")
+    } else {
+        String::new()
+    };
+    if repeat > 1 {
+        println!(
+            "day    part   {:width$}  {:>9}  {:>9}{allocations_header}",
+            "answer", "min", "median"
+        );
+    } else {
+        println!(
+            "day    part   {:width$}  {:>9}{allocations_header}",
+            "answer", "time"
+        );
+    }
+
+    for r in &rows {
+        print_row(r, width, repeat, allocations);
+    }
+
+    let padding = if repeat > 1 { 11 } else { 0 };
+    println!(
+        "total  {:5}  {:width$}  {:padding$}{:>9}",
+        "",
+        "",
+        "",
+        format_duration(total)
+    );
+    println!(
+        "wall   {:5}  {:width$}  {:padding$}{:>9}",
+        "",
+        "",
+        "",
+        format_duration(wall)
+    );
+}
+
+/// Prints one step with its times, its allocations if they were counted, and the picture behind
+/// its answer drawn underneath
+fn print_row(r: &Row, width: usize, repeat: u32, allocations: bool) {
+    let times = match (r.min, r.median) {
+        (Some(min), Some(median)) if repeat > 1 => {
+            format!(
+                "{:>9}  {:>9}",
+                format_duration(min),
+                format_duration(median)
+            )
+        }
+        (_, Some(time)) => format!("{:>9}", format_duration(time)),
+        _ => String::new(),
+    };
+    let allocated = match r.allocations {
+        Some(a) if allocations => {
+            format!("  {:>9}  {:>9}", a.count, format_bytes(a.peak_bytes))
+        }
+        _ => String::new(),
+    };
+    println!(
+        "day{:02}  {:5}  {:width$}  {times}{allocated}",
+        r.day, r.step, r.answer
+    );
+    if let Some(image) = &r.image {
+        print!("{image}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_quotes_fields() {
+        let csv = to_csv(&[Record {
+            day: 10,
+            part: Part::B,
+            answer: Some("#.\n.#\n".to_owned()),
+            answer_type: Some("image"),
+            image: None,
+            parse_ns: Some(1),
+            duration_ns: Some(2),
+            error: None,
+            parse_allocations: None,
+            parse_peak_bytes: None,
+            allocations: Some(3),
+            peak_bytes: Some(4),
+        }]);
+        assert_eq!(
+            csv,
+            "day,part,answer,answer_type,parse_ns,duration_ns,error,\
+             parse_allocations,parse_peak_bytes,allocations,peak_bytes\n\
+             10,b,\"#.\n.#\n\",image,1,2,,,,3,4\n"
+        );
+    }
+}
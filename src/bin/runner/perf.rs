@@ -0,0 +1,309 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use advent_of_code_2022::{
+    history::{self, History, Timing},
+    timing::format_duration,
+    Part,
+};
+use anyhow::{anyhow, Context};
+use clap::Args;
+use itertools::Itertools;
+
+use crate::{DayResult, RunArgs};
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// File holding the benchmark history
+    #[arg(long, default_value = history::DEFAULT_PATH)]
+    history: PathBuf,
+
+    /// Name of the machine the times are from, as only times from the same machine are compared.
+    /// Defaults to the host name
+    #[arg(long, env = "AOC_MACHINE")]
+    machine: Option<String>,
+}
+
+impl HistoryArgs {
+    fn machine(&self) -> String {
+        self.machine.clone().unwrap_or_else(host_name)
+    }
+}
+
+fn host_name() -> String {
+    env::var("HOSTNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_owned())
+}
+
+/// Runs git in a directory, returning what it printed
+fn git(dir: &Path, args: &[&str]) -> Result<String, anyhow::Error> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .context("unable to run git to find the commit")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "unable to find the commit: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_owned())
+}
+
+/// The median time of every part that gave an answer
+fn medians(results: &[DayResult]) -> BTreeMap<(u32, Part), Duration> {
+    results
+        .iter()
+        .filter_map(|r| Some((r.day, r.measured.as_ref().ok()?)))
+        .flat_map(|(day, m)| {
+            m.parts
+                .iter()
+                .filter(|p| p.answer.is_ok())
+                .map(move |p| ((day, p.part), p.times.median()))
+        })
+        .collect()
+}
+
+/// Adds the times of a run to the history, against the commit that was checked out when it started
+pub struct Recorder<'a> {
+    args: &'a HistoryArgs,
+    commit: String,
+}
+
+impl<'a> Recorder<'a> {
+    /// Finds the commit of the repository to record against, refusing if the working tree has
+    /// changed since it, as the times would not be for that commit. The history itself is left
+    /// out, so that a recording can follow another before it is committed
+    pub fn new(args: &'a HistoryArgs, repo: &Path) -> Result<Self, anyhow::Error> {
+        let commit = git(repo, &["rev-parse", "HEAD"])?;
+        // git can't leave out an absolute path, so it is given relative to the repository
+        let history = args.history.strip_prefix(repo).unwrap_or(&args.history);
+        let history = format!(":!{}", history.display());
+        if !git(repo, &["status", "--porcelain", "--", ":/", &history])?.is_empty() {
+            return Err(anyhow!(
+                "not recording times against {}, the working tree has uncommitted changes",
+                short(&commit)
+            ));
+        }
+        Ok(Self { args, commit })
+    }
+
+    pub fn record(&self, results: &[DayResult]) -> Result<(), anyhow::Error> {
+        let machine = self.args.machine();
+        let recorded = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+        let mut history = History::load(&self.args.history)?;
+        history.record(
+            medians(results)
+                .into_iter()
+                .map(|((day, part), median)| Timing {
+                    commit: self.commit.clone(),
+                    machine: machine.clone(),
+                    recorded,
+                    day,
+                    part,
+                    median_ns: u64::try_from(median.as_nanos()).unwrap_or(u64::MAX),
+                }),
+        );
+        history.save(&self.args.history)
+    }
+}
+
+/// How many percent slower parts can get before they are flagged, for every day or just one
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    day: Option<u32>,
+    percent: f64,
+}
+
+impl FromStr for Threshold {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (day, percent) = match s.split_once('=') {
+            Some((day, percent)) => (Some(day.trim().parse()?), percent),
+            None => (None, s),
+        };
+        let percent = percent.trim().trim_end_matches('%').parse()?;
+        Ok(Self { day, percent })
+    }
+}
+
+/// The threshold for a day, its own if it has one and otherwise the last one for every day
+fn threshold(thresholds: &[Threshold], day: u32) -> f64 {
+    thresholds
+        .iter()
+        .rev()
+        .find(|t| t.day == Some(day))
+        .or_else(|| thresholds.iter().rev().find(|t| t.day.is_none()))
+        .map_or(10.0, |t| t.percent)
+}
+
+/// Runs the days and prints how their median times have changed since the baseline, returning the
+/// number of days with a part that slowed down by more than its threshold
+pub fn perf_diff(
+    args: &RunArgs,
+    repeat: u32,
+    baseline: Option<&str>,
+    thresholds: &[Threshold],
+    history_args: &HistoryArgs,
+    record_run: bool,
+) -> Result<usize, anyhow::Error> {
+    let history = History::load(&history_args.history)?;
+    let machine = history_args.machine();
+    let commit = match baseline {
+        Some(commit) => commit,
+        None => history.latest_commit(&machine).ok_or_else(|| {
+            anyhow!(
+                "no times are recorded for {machine} in {}, record some with `runner --record`",
+                history_args.history.display()
+            )
+        })?,
+    };
+    let baseline = history.baseline(commit, &machine);
+    if baseline.is_empty() {
+        return Err(anyhow!(
+            "no times are recorded for commit {commit} on {machine}"
+        ));
+    }
+    let recorder = record_run
+        .then(|| Recorder::new(history_args, Path::new(".")))
+        .transpose()?;
+
+    let (results, _) = crate::measure_days(args, repeat, 1)?;
+    let current = medians(&results);
+
+    println!("comparing against {} on {machine}", short(commit));
+    println!(
+        "day    part   {:>9}  {:>9}  {:>8}",
+        "baseline", "current", "change"
+    );
+    let mut regressed = Vec::new();
+    for r in &results {
+        for &part in &r.parts {
+            let before = baseline.get(&(r.day, part)).copied();
+            let after = current.get(&(r.day, part)).copied();
+            let (change, flag) = match (before, after) {
+                (Some(before), Some(after)) => {
+                    let change = history::change(before, after);
+                    let flag = if change > threshold(thresholds, r.day) {
+                        regressed.push(r.day);
+                        "  REGRESSION"
+                    } else {
+                        ""
+                    };
+                    (format!("{change:+.1}%"), flag)
+                }
+                (None, Some(_)) => ("new".to_owned(), ""),
+                (_, None) => ("-".to_owned(), ""),
+            };
+            println!(
+                "day{:02}  {:5}  {:>9}  {:>9}  {change:>8}{flag}",
+                r.day,
+                part.to_string(),
+                before.map_or_else(|| "-".to_owned(), format_duration),
+                after.map_or_else(|| "-".to_owned(), format_duration),
+            );
+        }
+    }
+
+    if let Some(recorder) = recorder {
+        recorder.record(&results)?;
+    }
+    Ok(regressed.into_iter().unique().count())
+}
+
+/// Enough of a commit's hash to recognise it
+fn short(commit: &str) -> &str {
+    commit.get(..10).unwrap_or(commit)
+}
+
+#[cfg(test)]
+mod tests {
+    use advent_of_code_2022::{allocations::Allocations, timing::Timings, Answer};
+
+    use super::*;
+    use crate::{Measured, MeasuredPart};
+
+    #[test]
+    #[allow(clippy::float_cmp)] // The thresholds are parsed, not calculated
+    fn thresholds() -> Result<(), anyhow::Error> {
+        let thresholds = ["5".parse()?, "16=25%".parse()?];
+        assert_eq!(threshold(&thresholds, 16), 25.0);
+        assert_eq!(threshold(&thresholds, 15), 5.0);
+        assert_eq!(threshold(&thresholds[1..], 15), 10.0);
+        assert!("16=x".parse::<Threshold>().is_err());
+        assert!("x=5".parse::<Threshold>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn records_twice_in_a_row() -> Result<(), anyhow::Error> {
+        let repo = env::temp_dir().join(format!("aoc-record-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&repo);
+        fs::create_dir_all(&repo)?;
+        fs::write(repo.join("README.md"), "# Advent of Code\n")?;
+        git(&repo, &["init", "-q"])?;
+        git(&repo, &["add", "-A"])?;
+        git(
+            &repo,
+            &[
+                "-c",
+                "user.name=aoc",
+                "-c",
+                "user.email=aoc@example.com",
+                "commit",
+                "-qm",
+                "first",
+            ],
+        )?;
+
+        let args = HistoryArgs {
+            history: repo.join(history::DEFAULT_PATH),
+            machine: Some("test".to_owned()),
+        };
+        let mut times = Timings::default();
+        times.push(Duration::from_millis(3));
+        let results = [DayResult {
+            day: 1,
+            parts: vec![Part::A],
+            input: None,
+            measured: Ok(Measured {
+                parse: Timings::default(),
+                parse_allocations: Allocations::default(),
+                parts: vec![MeasuredPart {
+                    part: Part::A,
+                    answer: Ok(Answer::Integer(24_000)),
+                    times,
+                    allocations: Allocations::default(),
+                }],
+            }),
+        }];
+
+        Recorder::new(&args, &repo)?.record(&results)?;
+        Recorder::new(&args, &repo)?.record(&results)?;
+        let head = git(&repo, &["rev-parse", "HEAD"])?;
+        assert_eq!(
+            History::load(&args.history)?.latest_commit("test"),
+            Some(&*head)
+        );
+
+        // Any other change still stops it
+        fs::write(repo.join("README.md"), "# Advent of Code 2022\n")?;
+        assert!(Recorder::new(&args, &repo).is_err());
+
+        fs::remove_dir_all(&repo)?;
+        Ok(())
+    }
+}
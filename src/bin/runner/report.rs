@@ -0,0 +1,311 @@
+use std::{fmt::Write, fs, path::Path, time::Duration};
+
+use advent_of_code_2022::{
+    answers::{AnswerStore, Verdict},
+    inputs, svg,
+    timing::format_duration,
+    Answer, DynSolution, Part,
+};
+use anyhow::Context;
+
+use crate::{DayResult, RunArgs};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; border-bottom: 1px solid #ddd; text-align: left; vertical-align: middle; }
+td.answer { font-family: monospace; }
+.PASS { color: #1a7f37; } .FAIL, .ERROR, .TIMEOUT { color: #cf222e; font-weight: bold; } .MISSING { color: #9a6700; }
+.bar { display: inline-block; height: 0.8em; background: #4f81bd; margin-right: 0.5em; }
+.time { white-space: nowrap; }
+";
+
+/// One day's row of the report
+struct ReportDay {
+    day: u32,
+    title: &'static str,
+    /// The file its visualisation was written to, if it has one
+    visualisation: Option<String>,
+    parts: Vec<ReportPart>,
+}
+
+struct ReportPart {
+    part: Part,
+    answer: Option<Answer>,
+    /// PASS, FAIL or MISSING against the store, or why there is no answer
+    status: String,
+    /// What the store expected, for a wrong answer
+    expected: Option<Answer>,
+    median: Option<Duration>,
+}
+
+impl ReportPart {
+    fn failed(&self) -> bool {
+        !matches!(self.status.as_str(), "PASS" | "MISSING")
+    }
+}
+
+/// Runs the days and writes a page of their answers, whether they are right and how long they
+/// took to `index.html` in `out`, along with the visualisations of the days that have one.
+/// Returns the number of days with a wrong or failing part.
+pub fn report(
+    args: &RunArgs,
+    repeat: u32,
+    answers: &AnswerStore,
+    out: &Path,
+) -> Result<usize, anyhow::Error> {
+    fs::create_dir_all(out)
+        .with_context(|| format!("unable to create report directory {}", out.display()))?;
+    let (results, wall) = crate::measure_days(args, repeat, 1)?;
+
+    let mut days = Vec::new();
+    for (s, result) in args.solutions()?.into_iter().zip(results) {
+        let (digest, visualisation) = match &result.input {
+            Some(input) => (inputs::digest(input), visualise(s, input, out)?),
+            None => (String::new(), None),
+        };
+        days.push(ReportDay {
+            day: s.day(),
+            title: s.title(),
+            visualisation,
+            parts: report_parts(&result, answers, &digest),
+        });
+    }
+
+    let path = out.join("index.html");
+    fs::write(&path, render(&days, repeat, wall)?)
+        .with_context(|| format!("unable to write report to {}", path.display()))?;
+    println!("wrote {}", path.display());
+
+    Ok(days
+        .iter()
+        .filter(|d| d.parts.iter().any(ReportPart::failed))
+        .count())
+}
+
+/// Writes the day's visualisation next to the report, returning its file name
+fn visualise(
+    s: &dyn DynSolution,
+    input: &str,
+    out: &Path,
+) -> Result<Option<String>, anyhow::Error> {
+    let picture = match s.visualise(input) {
+        Ok(Some(picture)) => picture,
+        Ok(None) => return Ok(None),
+        Err(e) => {
+            eprintln!("day{:02}: unable to visualise: {e:#}", s.day());
+            return Ok(None);
+        }
+    };
+    let name = format!("day{:02}.svg", s.day());
+    let path = out.join(&name);
+    fs::write(&path, picture)
+        .with_context(|| format!("unable to write visualisation to {}", path.display()))?;
+    Ok(Some(name))
+}
+
+/// Checks each part's answer against the store
+fn report_parts(result: &DayResult, answers: &AnswerStore, digest: &str) -> Vec<ReportPart> {
+    let failed = |part, e| ReportPart {
+        part,
+        answer: None,
+        status: crate::failure(e).to_owned(),
+        expected: None,
+        median: None,
+    };
+    let measured = match &result.measured {
+        Ok(measured) => measured,
+        Err(e) => return result.parts.iter().map(|&p| failed(p, e)).collect(),
+    };
+
+    measured
+        .parts
+        .iter()
+        .map(|p| match &p.answer {
+            Ok(answer) => {
+                let verdict = answers.check(result.day, p.part, digest, answer);
+                ReportPart {
+                    part: p.part,
+                    answer: Some(answer.clone()),
+                    status: verdict.to_string(),
+                    expected: match verdict {
+                        Verdict::Fail { expected } => Some(expected),
+                        Verdict::Pass | Verdict::Missing => None,
+                    },
+                    median: Some(p.times.median()),
+                }
+            }
+            Err(e) => failed(p.part, e),
+        })
+        .collect()
+}
+
+/// The whole page, with everything but the visualisations inline so it can be shared on its own
+fn render(days: &[ReportDay], repeat: u32, wall: Duration) -> Result<String, anyhow::Error> {
+    let slowest = days
+        .iter()
+        .flat_map(|d| &d.parts)
+        .filter_map(|p| p.median)
+        .max()
+        .unwrap_or_default();
+
+    let mut html = String::new();
+    writeln!(html, "<!DOCTYPE html>")?;
+    writeln!(html, r#"<html lang="en"><head><meta charset="utf-8">"#)?;
+    writeln!(html, "<title>Advent of Code 2022</title>")?;
+    writeln!(html, "<style>{STYLE}</style></head><body>")?;
+    writeln!(html, "<h1>Advent of Code 2022</h1>")?;
+    writeln!(
+        html,
+        "<p>{} days in {}, median of {repeat} run{}. Time bars are on a log scale.</p>",
+        days.len(),
+        format_duration(wall),
+        if repeat == 1 { "" } else { "s" },
+    )?;
+    writeln!(html, "<table>")?;
+    writeln!(
+        html,
+        "<tr><th>Day</th><th>Part</th><th>Answer</th><th>Status</th><th>Time</th></tr>"
+    )?;
+    for day in days {
+        render_day(&mut html, day, slowest)?;
+    }
+    writeln!(html, "</table>\n</body></html>")?;
+    Ok(html)
+}
+
+fn render_day(html: &mut String, day: &ReportDay, slowest: Duration) -> Result<(), anyhow::Error> {
+    for (i, p) in day.parts.iter().enumerate() {
+        write!(html, "<tr>")?;
+        if i == 0 {
+            write!(
+                html,
+                r#"<td rowspan="{}">day{:02}: {}"#,
+                day.parts.len(),
+                day.day,
+                escape(day.title)
+            )?;
+            if let Some(file) = &day.visualisation {
+                write!(html, r#"<br><a href="{}">visualisation</a>"#, escape(file))?;
+            }
+            write!(html, "</td>")?;
+        }
+        write!(html, "<td>{}</td>", p.part)?;
+        write!(html, r#"<td class="answer">{}</td>"#, answer_cell(p))?;
+        match &p.expected {
+            Some(expected) => write!(
+                html,
+                r#"<td class="{0}" title="expected {1}">{0}</td>"#,
+                p.status,
+                escape(&expected.to_string())
+            )?,
+            None => write!(html, r#"<td class="{0}">{0}</td>"#, p.status)?,
+        }
+        match p.median {
+            Some(median) => write!(
+                html,
+                r#"<td class="time"><span class="bar" style="width: {:.1}em"></span>{}</td>"#,
+                bar_width(median, slowest),
+                format_duration(median)
+            )?,
+            None => write!(html, "<td></td>")?,
+        }
+        writeln!(html, "</tr>")?;
+    }
+    Ok(())
+}
+
+/// The answer as text, with any picture it was read from drawn as pixels
+fn answer_cell(p: &ReportPart) -> String {
+    let Some(answer) = &p.answer else {
+        return String::new();
+    };
+    let text = answer.to_string();
+    match answer.image() {
+        Some(image) if image == text => svg::picture(image, "currentColor"),
+        Some(image) => format!(
+            "{}<br>{}",
+            escape(&text),
+            svg::picture(image, "currentColor")
+        ),
+        None => escape(&text),
+    }
+}
+
+/// How many ems long a time's bar is, out of 10 for the slowest, on a log scale so that parts
+/// taking microseconds still show up next to ones taking seconds
+fn bar_width(time: Duration, slowest: Duration) -> f64 {
+    let log = |d: Duration| d.as_secs_f64().mul_add(1e9, 1.0).log10();
+    if slowest.is_zero() {
+        0.0
+    } else {
+        10.0 * log(time) / log(slowest)
+    }
+}
+
+/// Text safe to put in the page, or in one of its attributes
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn bar_widths() {
+        let ms = Duration::from_millis;
+        assert!((bar_width(ms(100), ms(100)) - 10.0).abs() < 1e-9);
+        assert!(bar_width(Duration::from_micros(10), ms(100)) > 4.0);
+        assert!(bar_width(ms(1), ms(100)) < bar_width(ms(10), ms(100)));
+        assert!(bar_width(Duration::ZERO, Duration::ZERO).abs() < 1e-9);
+    }
+
+    #[test]
+    fn renders_rows() -> Result<(), anyhow::Error> {
+        let part = |part, answer: Answer, status: &str| ReportPart {
+            part,
+            answer: Some(answer),
+            status: status.to_owned(),
+            expected: (status == "FAIL").then(|| Answer::from(2)),
+            median: Some(Duration::from_millis(5)),
+        };
+        let days = [ReportDay {
+            day: 10,
+            title: "Cathode-Ray Tube",
+            visualisation: Some("day10.svg".to_owned()),
+            parts: vec![
+                part(Part::A, Answer::from(1), "FAIL"),
+                part(Part::B, Answer::Image("#.\n.#".to_owned()), "MISSING"),
+            ],
+        }];
+        let html = render(&days, 1, Duration::from_millis(10))?;
+
+        assert!(html.contains(r#"<td rowspan="2">day10: Cathode-Ray Tube"#));
+        assert!(html.contains(r#"<a href="day10.svg">visualisation</a>"#));
+        assert!(html.contains(r#"<td class="FAIL" title="expected 2">FAIL</td>"#));
+        assert!(html.contains(r#"<td class="answer"><svg"#));
+        assert!(html.contains(r#"<span class="bar" style="width: 10.0em">"#));
+        assert!(days[0].parts[0].failed() && !days[0].parts[1].failed());
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::ErrorKind,
+    path::Path,
+};
+
+use advent_of_code_2022::inputs;
+use anyhow::{anyhow, Context};
+
+const TEMPLATE: &str = include_str!("day.rs.tmpl");
+const FUZZ_TEMPLATE: &str = include_str!("fuzz_target.rs.tmpl");
+/// Registers a day's fuzz target with the fuzz crate
+const FUZZ_BIN: &str = r#"
+[[bin]]
+name = "day{{DAY}}"
+path = "fuzz_targets/day{{DAY}}.rs"
+test = false
+doc = false
+bench = false
+"#;
+
+/// Generates the module and fuzz target for a new day, creates its empty example and input files
+/// and registers it with the library, the examples manifest and the fuzz crate, returning the files
+/// touched
+pub fn new_day(root: &Path, day: u32, title: &str) -> Result<Vec<String>, anyhow::Error> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("there is no day {day}"));
+    }
+
+    let module = root.join(format!("src/day{day:02}.rs"));
+    if module.exists() {
+        return Err(anyhow!("{} already exists", module.display()));
+    }
+
+    // Work out every edit before writing anything, so a failure leaves the tree untouched
+    let lib = root.join("src/lib.rs");
+    let lib_content = edit(&lib, |content| {
+        let content = insert_line(content, day, &format!("pub mod day{day:02};"), |l| {
+            l.strip_prefix("pub mod day")?
+                .strip_suffix(';')?
+                .parse()
+                .ok()
+        })?;
+        insert_line(&content, day, &format!("&day{day:02}::Day{day:02},"), |l| {
+            l.trim()
+                .strip_prefix("&day")?
+                .split_once("::")?
+                .0
+                .parse()
+                .ok()
+        })
+    })?;
+    let embedded = root.join("src/inputs.rs");
+    let embedded_content = edit(&embedded, |content| {
+        let line = format!(
+            "{day} => include_str!(\"../{}/{}\"),",
+            inputs::DEFAULT_DIR,
+            inputs::file_name(day)
+        );
+        insert_line(content, day, &line, |l| {
+            l.trim().split_once(" => include_str!")?.0.parse().ok()
+        })
+    })?;
+
+    let example = format!("example{day:02}.txt");
+    let manifest = root
+        .join(inputs::DEFAULT_DIR)
+        .join(inputs::EXAMPLES_MANIFEST);
+    let manifest_content = edit(&manifest, |content| {
+        if content.contains(&format!("[\"{example}\"]")) {
+            return Err(anyhow!("{example} is already listed"));
+        }
+        Ok(format!(
+            "{}\n\n[\"{example}\"]\n# a = \"\"\n# b = \"\"\n",
+            content.trim_end()
+        ))
+    })?;
+
+    let fuzz = root.join("fuzz/Cargo.toml");
+    let fuzz_content = edit(&fuzz, |content| {
+        if content.contains(&format!("name = \"day{day:02}\"")) {
+            return Err(anyhow!("day{day:02} already has a fuzz target"));
+        }
+        Ok(format!(
+            "{}\n{}",
+            content.trim_end(),
+            render(FUZZ_BIN, day, title)
+        ))
+    })?;
+
+    let mut touched = Vec::new();
+    let fuzz_target = root.join(format!("fuzz/fuzz_targets/day{day:02}.rs"));
+    for (path, content) in [
+        (&module, render(TEMPLATE, day, title)),
+        (&fuzz_target, render(FUZZ_TEMPLATE, day, title)),
+    ] {
+        fs::write(path, content).with_context(|| format!("unable to write {}", path.display()))?;
+        touched.push(path.display().to_string());
+    }
+
+    for file in [example, inputs::file_name(day)] {
+        let path = root.join(inputs::DEFAULT_DIR).join(file);
+        // Leave any input that has already been fetched alone
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => touched.push(path.display().to_string()),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("unable to create {}", path.display()))
+            }
+        }
+    }
+
+    for (path, content) in [
+        (lib, lib_content),
+        (embedded, embedded_content),
+        (manifest, manifest_content),
+        (fuzz, fuzz_content),
+    ] {
+        fs::write(&path, content).with_context(|| format!("unable to write {}", path.display()))?;
+        touched.push(path.display().to_string());
+    }
+
+    Ok(touched)
+}
+
+fn edit(
+    path: &Path,
+    f: impl FnOnce(&str) -> Result<String, anyhow::Error>,
+) -> Result<String, anyhow::Error> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+    f(&content).with_context(|| format!("unable to register the day in {}", path.display()))
+}
+
+fn render(template: &str, day: u32, title: &str) -> String {
+    template
+        .replace("{{DAY}}", &format!("{day:02}"))
+        .replace("{{DAY_NUMBER}}", &day.to_string())
+        .replace(
+            "{{TITLE}}",
+            &title.replace('\\', "\\\\").replace('"', "\\\""),
+        )
+}
+
+/// Adds `line` among the existing lines that `day_of` recognises, keeping them in day order and
+/// matching their indentation
+fn insert_line(
+    content: &str,
+    day: u32,
+    line: &str,
+    day_of: impl Fn(&str) -> Option<u32>,
+) -> Result<String, anyhow::Error> {
+    let lines = content.lines().collect::<Vec<_>>();
+    let days = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| Some((i, day_of(l)?)))
+        .collect::<Vec<_>>();
+
+    if days.iter().any(|&(_, d)| d == day) {
+        return Err(anyhow!("day {day} is already registered"));
+    }
+    let (neighbour, at) = if let Some(&(i, _)) = days.iter().rev().find(|&&(_, d)| d < day) {
+        (i, i + 1)
+    } else {
+        let &(i, _) = days.first().ok_or_else(|| anyhow!("no days found"))?;
+        (i, i)
+    };
+
+    let neighbour = lines[neighbour];
+    let indent = &neighbour[..neighbour.len() - neighbour.trim_start().len()];
+    let line = format!("{indent}{line}");
+    let mut lines = lines;
+    lines.insert(at, &line);
+
+    Ok(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_in_day_order() -> Result<(), anyhow::Error> {
+        let lib =
+            "pub mod day01;\npub mod day03;\n\nstatic S: &[u32] = &[\n    &day01::Day01,\n];\n";
+        let day_of = |l: &str| {
+            l.strip_prefix("pub mod day")?
+                .strip_suffix(';')?
+                .parse()
+                .ok()
+        };
+
+        assert_eq!(
+            insert_line(lib, 2, "pub mod day02;", day_of)?,
+            lib.replace("day01;\n", "day01;\npub mod day02;\n")
+        );
+        assert_eq!(
+            insert_line(lib, 18, "pub mod day18;", day_of)?,
+            lib.replace("day03;\n", "day03;\npub mod day18;\n")
+        );
+        assert!(insert_line(lib, 3, "pub mod day03;", day_of).is_err());
+
+        let registered = insert_line(lib, 2, "&day02::Day02,", |l| {
+            l.trim()
+                .strip_prefix("&day")?
+                .split_once("::")?
+                .0
+                .parse()
+                .ok()
+        })?;
+        assert!(registered.contains("    &day01::Day01,\n    &day02::Day02,\n];"));
+        Ok(())
+    }
+
+    #[test]
+    fn renders_template() {
+        let module = render(TEMPLATE, 18, "Boiling \"Boulders\"");
+        assert!(module.contains("pub struct Day18;"));
+        assert!(module.contains("const DAY: u32 = 18;"));
+        assert!(module.contains(r#"const TITLE: &'static str = "Boiling \"Boulders\"";"#));
+        assert!(!module.contains("{{"));
+
+        let target = render(FUZZ_TEMPLATE, 18, "Boiling Boulders");
+        assert!(target.contains("use advent_of_code_2022::{day18::Day18, DynSolution, Part};"));
+        assert!(!target.contains("{{"));
+    }
+
+    #[test]
+    fn scaffolds_into_a_tree() -> Result<(), anyhow::Error> {
+        let root = std::env::temp_dir().join(format!("aoc-new-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src"))?;
+        fs::create_dir_all(root.join("resources"))?;
+        fs::create_dir_all(root.join("fuzz/fuzz_targets"))?;
+        // Just the parts of each file that are edited, so the days in the real tree don't matter
+        for (file, content) in [
+            (
+                "src/lib.rs",
+                "pub mod day01;\npub mod day17;\n\npub static SOLUTIONS: &[&dyn DynSolution] = &[\n    &day01::Day01,\n    &day17::Day17,\n];\n",
+            ),
+            (
+                "src/inputs.rs",
+                "    Some(match day {\n        1 => include_str!(\"../resources/input01.txt\"),\n        17 => include_str!(\"../resources/input17.txt\"),\n        _ => return None,\n    })\n",
+            ),
+            ("resources/examples.toml", "[\"example17.txt\"]\na = \"3068\"\n"),
+            (
+                "fuzz/Cargo.toml",
+                "[package]\nname = \"fuzz\"\n\n[[bin]]\nname = \"day17\"\npath = \"fuzz_targets/day17.rs\"\n",
+            ),
+            ("resources/input18.txt", "1,1,1\n"),
+        ] {
+            fs::write(root.join(file), content)?;
+        }
+
+        let touched = new_day(&root, 18, "Boiling Boulders");
+        let lib = fs::read_to_string(root.join("src/lib.rs"))?;
+        let embedded = fs::read_to_string(root.join("src/inputs.rs"))?;
+        let input = fs::read_to_string(root.join("resources/input18.txt"))?;
+        let manifest = fs::read_to_string(root.join("resources/examples.toml"))?;
+        let fuzz = fs::read_to_string(root.join("fuzz/Cargo.toml"))?;
+        let again = new_day(&root, 18, "Boiling Boulders");
+        fs::remove_dir_all(&root)?;
+
+        assert_eq!(touched?.len(), 7);
+        assert!(lib.contains("pub mod day17;\npub mod day18;\n"));
+        assert!(lib.contains("    &day17::Day17,\n    &day18::Day18,\n];"));
+        assert!(embedded.contains("        18 => include_str!(\"../resources/input18.txt\"),\n"));
+        assert_eq!(input, "1,1,1\n");
+        assert!(manifest.ends_with("\n\n[\"example18.txt\"]\n# a = \"\"\n# b = \"\"\n"));
+        assert!(fuzz.ends_with("[[bin]]\nname = \"day18\"\npath = \"fuzz_targets/day18.rs\"\ntest = false\ndoc = false\nbench = false\n"));
+        assert!(again.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use std::{
+    cell::Cell,
+    error::Error,
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering::Relaxed},
+    time::{Duration, Instant},
+};
+
+use crate::timing::format_duration;
+
+/// How long each part may run for in nanoseconds, or zero for as long as it likes
+static BUDGET: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static CURRENT: Cell<Token> = const { Cell::new(Token { deadline: None }) };
+}
+
+/// Gives every part solved from now on, on any thread, this long before it is asked to give up
+pub fn set_budget(budget: Option<Duration>) {
+    let nanos = budget.map_or(0, |b| u64::try_from(b.as_nanos()).unwrap_or(u64::MAX));
+    BUDGET.store(nanos, Relaxed);
+}
+
+fn budget() -> Option<Duration> {
+    match BUDGET.load(Relaxed) {
+        0 => None,
+        nanos => Some(Duration::from_nanos(nanos)),
+    }
+}
+
+/// The error a part gives up with when it runs out of time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedOut {
+    pub budget: Duration,
+}
+
+impl Display for TimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ran out of its {} time budget",
+            format_duration(self.budget)
+        )
+    }
+}
+
+impl Error for TimedOut {}
+
+/// Whether an error, or anything that caused it, is a part running out of time
+#[must_use]
+pub fn timed_out(e: &anyhow::Error) -> bool {
+    e.chain().any(<dyn Error>::is::<TimedOut>)
+}
+
+/// Tells a long running part whether it has run out of time. Loops that could take a long time
+/// check it every so often and give up with its error, which is copied into any threads they
+/// start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Token {
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl Token {
+    /// The token for the part being solved on this thread, which never runs out outside of one
+    #[must_use]
+    pub fn current() -> Self {
+        CURRENT.get()
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.deadline
+            .is_some_and(|(deadline, _)| Instant::now() >= deadline)
+    }
+
+    /// Fails once the part has run out of time
+    pub fn check(&self) -> Result<(), TimedOut> {
+        match self.deadline {
+            Some((deadline, budget)) if Instant::now() >= deadline => Err(TimedOut { budget }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Starts the clock on a part solved on this thread, until the returned guard is dropped
+pub(crate) fn start() -> Started {
+    let token = Token {
+        deadline: budget().map(|b| (Instant::now() + b, b)),
+    };
+    Started(CURRENT.replace(token))
+}
+
+/// Puts back the token of whatever was running before a part started
+pub(crate) struct Started(Token);
+
+impl Drop for Started {
+    fn drop(&mut self) {
+        CURRENT.set(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn never_cancelled_outside_a_part() {
+        assert!(!Token::current().is_cancelled());
+        assert_eq!(Token::current().check(), Ok(()));
+    }
+
+    #[test]
+    fn cancelled_once_the_deadline_passes() {
+        let budget = Duration::from_millis(1);
+        let token = Token {
+            deadline: Some((Instant::now() + budget, budget)),
+        };
+        std::thread::sleep(budget * 2);
+        assert!(token.is_cancelled());
+        assert_eq!(token.check(), Err(TimedOut { budget }));
+        assert!(timed_out(
+            &anyhow::Error::from(TimedOut { budget }).context("day15b")
+        ));
+        assert!(!timed_out(&anyhow::anyhow!("no route")));
+    }
+}
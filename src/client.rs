@@ -0,0 +1,381 @@
+use std::{
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context};
+use ureq::{http::Response, Agent, Body};
+
+use crate::{answers::Hint, Answer, Inputs, Part};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const YEAR: u32 = 2022;
+const USER_AGENT: &str = concat!(
+    "advent-of-code-2022/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/dlkj/advent-of-code)"
+);
+
+/// Talks to the Advent of Code site, or anything else serving the same paths at `base_url`
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: Agent,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// What the site made of a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Wrong(Option<Hint>),
+    /// Rate limited, nothing was checked
+    Wait(Duration),
+    /// The part has already been solved, nothing was checked
+    AlreadySolved,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::Wrong(Some(hint)) => write!(f, "wrong, {hint}"),
+            Self::Wrong(None) => write!(f, "wrong"),
+            Self::Wait(wait) => write!(f, "answered too recently, wait {}s", wait.as_secs()),
+            Self::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    /// Reads the outcome from the text of the page returned for a submission
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("That's the right answer") {
+            Ok(Self::Correct)
+        } else if s.contains("That's not the right answer") {
+            Ok(Self::Wrong(if s.contains("too high") {
+                Some(Hint::TooHigh)
+            } else if s.contains("too low") {
+                Some(Hint::TooLow)
+            } else {
+                None
+            }))
+        } else if s.contains("You gave an answer too recently") {
+            Ok(Self::Wait(wait_time(s).unwrap_or(Duration::from_mins(1))))
+        } else if s.contains("You don't seem to be solving the right level") {
+            Ok(Self::AlreadySolved)
+        } else {
+            Err(anyhow!("unrecognised response to submission"))
+        }
+    }
+}
+
+/// Finds how long to wait in text like "you have 4m 32s left to wait" or "wait 5 minutes"
+fn wait_time(s: &str) -> Option<Duration> {
+    if let Some((_, rest)) = s.split_once("you have ") {
+        let (left, _) = rest.split_once(" left")?;
+        return left
+            .split_whitespace()
+            .map(|t| {
+                let (n, unit) = t.split_at(t.find(|c: char| !c.is_ascii_digit())?);
+                let n: u64 = n.parse().ok()?;
+                match unit {
+                    "h" => Some(n * 3600),
+                    "m" => Some(n * 60),
+                    "s" => Some(n),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+
+    let (_, rest) = s.split_once("wait ")?;
+    let (n, rest) = rest.split_once(' ')?;
+    rest.starts_with("minute")
+        .then(|| n.parse().ok())
+        .flatten()
+        .map(Duration::from_mins)
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: impl Into<String>) -> Self {
+        let agent = Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .http_status_as_error(false)
+            .timeout_global(Some(Duration::from_secs(30)))
+            .build()
+            .into();
+
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.into(),
+            agent,
+        }
+    }
+
+    fn url(&self, day: u32, path: &str) -> String {
+        format!("{}/{YEAR}/day/{day}{path}", self.base_url)
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Downloads the puzzle input for a day
+    pub fn input(&self, day: u32) -> Result<String, anyhow::Error> {
+        let url = self.url(day, "/input");
+        let response = self
+            .agent
+            .get(&url)
+            .header("Cookie", &self.cookie())
+            .call()
+            .with_context(|| format!("unable to request {url}"))?;
+
+        let body = body(response, &url, day)?;
+        if body.trim().is_empty() {
+            Err(anyhow!("empty input returned for day {day}"))
+        } else {
+            Ok(body)
+        }
+    }
+
+    /// Submits an answer for one part of a day
+    pub fn submit(&self, day: u32, part: Part, answer: &Answer) -> Result<Outcome, anyhow::Error> {
+        let url = self.url(day, "/answer");
+        let level = match part {
+            Part::A => "1",
+            Part::B => "2",
+        };
+        let response = self
+            .agent
+            .post(&url)
+            .header("Cookie", &self.cookie())
+            .send_form([("level", level), ("answer", &answer.to_string())])
+            .with_context(|| format!("unable to post to {url}"))?;
+
+        body(response, &url, day)?.parse()
+    }
+
+    /// Downloads a day's input into the inputs directory, unless it is already there
+    pub fn fetch(&self, inputs: &Inputs, day: u32) -> Result<Fetched, anyhow::Error> {
+        if let Some(path) = inputs.cached(day) {
+            return Ok(Fetched::Cached(path));
+        }
+
+        let input = self.input(day)?;
+        fs::create_dir_all(inputs.dir())
+            .with_context(|| format!("unable to create {}", inputs.dir().display()))?;
+        let path = inputs.path(day);
+        fs::write(&path, input).with_context(|| format!("unable to write {}", path.display()))?;
+        Ok(Fetched::Downloaded(path))
+    }
+}
+
+fn body(mut response: Response<Body>, url: &str, day: u32) -> Result<String, anyhow::Error> {
+    let status = response.status().as_u16();
+    match status {
+        200 => Ok(response.body_mut().read_to_string()?),
+        400 | 401 | 403 => Err(anyhow!("session token was rejected ({status})")),
+        404 => Err(anyhow!("day {day} is not available yet")),
+        _ => Err(anyhow!("unexpected response from {url} ({status})")),
+    }
+}
+
+/// Reads the session token from `AOC_SESSION`, falling back to a file
+pub fn session(file: &Path) -> Result<String, anyhow::Error> {
+    if let Ok(session) = std::env::var("AOC_SESSION") {
+        return Ok(session.trim().to_owned());
+    }
+
+    let session = fs::read_to_string(file).with_context(|| {
+        format!(
+            "no session token in AOC_SESSION or {}, copy the value of the session cookie there",
+            file.display()
+        )
+    })?;
+    Ok(session.trim().to_owned())
+}
+
+/// Where the session token is kept when it isn't in the environment, `~/.config/aoc/session`
+#[must_use]
+pub fn default_session_file() -> PathBuf {
+    std::env::var_os("HOME")
+        .map_or_else(PathBuf::new, PathBuf::from)
+        .join(".config/aoc/session")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+
+    /// Serves one canned `(status, body)` response per connection, returning each request's
+    /// request line, session cookie and body
+    fn serve(
+        responses: Vec<(u16, &'static str)>,
+    ) -> Result<(String, JoinHandle<Vec<String>>), anyhow::Error> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let Ok((stream, _)) = listener.accept() else {
+                    break;
+                };
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    let lower = line.to_ascii_lowercase();
+                    if let Some(len) = lower.strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap_or(0);
+                    }
+                    if request.is_empty() || lower.starts_with("cookie:") {
+                        request.push_str(line.trim_end());
+                        request.push('\n');
+                    }
+                    line.clear();
+                }
+                let mut content = vec![0; content_length];
+                if reader.read_exact(&mut content).is_ok() {
+                    request.push_str(&String::from_utf8_lossy(&content));
+                }
+                requests.push(request);
+
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+            requests
+        });
+
+        Ok((base_url, handle))
+    }
+
+    #[test]
+    fn downloads_input() -> Result<(), anyhow::Error> {
+        let (base_url, server) = serve(vec![(200, "1000\n2000\n")])?;
+
+        let input = Client::new(&base_url, "abc").input(1)?;
+
+        assert_eq!(input, "1000\n2000\n");
+        let requests = server.join().map_err(|_| anyhow!("server panicked"))?;
+        assert_eq!(
+            requests,
+            ["GET /2022/day/1/input HTTP/1.1\ncookie: session=abc\n"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_errors() -> Result<(), anyhow::Error> {
+        let (base_url, _server) = serve(vec![(400, "Please log in"), (404, "Not found")])?;
+        let client = Client::new(&base_url, "abc");
+
+        assert!(client.input(1).is_err());
+        assert!(client.input(25).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn submits_answers() -> Result<(), anyhow::Error> {
+        let (base_url, server) = serve(vec![
+            (200, "<article><p>That's the right answer!</p></article>"),
+            (
+                200,
+                "<p>That's not the right answer; your answer is too low.</p>",
+            ),
+        ])?;
+        let client = Client::new(&base_url, "abc");
+
+        assert_eq!(client.submit(1, Part::A, &24000.into())?, Outcome::Correct);
+        assert_eq!(
+            client.submit(5, Part::B, &Answer::from("MCD".to_owned()))?,
+            Outcome::Wrong(Some(Hint::TooLow))
+        );
+
+        let requests = server.join().map_err(|_| anyhow!("server panicked"))?;
+        assert_eq!(
+            requests,
+            [
+                "POST /2022/day/1/answer HTTP/1.1\ncookie: session=abc\nlevel=1&answer=24000",
+                "POST /2022/day/5/answer HTTP/1.1\ncookie: session=abc\nlevel=2&answer=MCD"
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parses_outcomes() -> Result<(), anyhow::Error> {
+        for (page, outcome) in [
+            ("That's the right answer! You are one gold star closer", Outcome::Correct),
+            (
+                "That's not the right answer; your answer is too high. Please wait one minute",
+                Outcome::Wrong(Some(Hint::TooHigh)),
+            ),
+            (
+                "That's not the right answer. If you're stuck, make sure you're using the full input",
+                Outcome::Wrong(None),
+            ),
+            (
+                "You gave an answer too recently; you have 4m 32s left to wait.",
+                Outcome::Wait(Duration::from_secs(272)),
+            ),
+            (
+                "You gave an answer too recently; please wait 5 minutes before trying again.",
+                Outcome::Wait(Duration::from_mins(5)),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Outcome::AlreadySolved,
+            ),
+        ] {
+            assert_eq!(page.parse::<Outcome>()?, outcome);
+        }
+        assert!("<html>Gateway Timeout</html>".parse::<Outcome>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn never_downloads_cached_inputs() -> Result<(), anyhow::Error> {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let inputs = Inputs::new(&dir);
+        let (base_url, server) = serve(vec![(200, "mjqjpqmgbljsphdztnvjfqwrcgsmlb")])?;
+        let client = Client::new(&base_url, "abc");
+
+        assert_eq!(
+            client.fetch(&inputs, 6)?,
+            Fetched::Downloaded(inputs.path(6))
+        );
+        assert_eq!(client.fetch(&inputs, 6)?, Fetched::Cached(inputs.path(6)));
+        assert_eq!(
+            server.join().map_err(|_| anyhow!("server panicked"))?.len(),
+            1
+        );
+        assert_eq!(inputs.load(6)?, "mjqjpqmgbljsphdztnvjfqwrcgsmlb");
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
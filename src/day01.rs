@@ -1,51 +1,73 @@
 use std::cmp::Reverse;
-use std::num::ParseIntError;
 
 use anyhow::anyhow;
-use itertools::process_results;
 use itertools::Itertools;
 
-use crate::DOUBLE_LINE_ENDING;
+use crate::{generate::Rng, inputs::blocks, Answer, Solution};
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(include_str!("../resources/input01.txt"))
-}
+pub struct Day01;
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(include_str!("../resources/input01.txt"))
-}
+impl Solution for Day01 {
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Calorie Counting";
+
+    /// The total calories carried by each elf
+    type Input<'a> = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    process_results(parse(input), |i| i.max())?.ok_or_else(|| anyhow!("No elfs found!"))
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input).into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let results = process_results(parse(input), |sums| {
-        sums.sorted_by_key(|k| Reverse(*k)).take(3).sum()
-    })?;
-    Ok(results)
+fn part_a(elves: &[u32]) -> Result<u32, anyhow::Error> {
+    elves
+        .iter()
+        .copied()
+        .max()
+        .ok_or_else(|| anyhow!("No elfs found!"))
 }
 
-fn parse(input: &str) -> impl Iterator<Item = Result<u32, ParseIntError>> + '_ {
-    input.split(DOUBLE_LINE_ENDING).map(parse_and_sum)
+fn part_b(elves: &[u32]) -> u64 {
+    elves
+        .iter()
+        .sorted_by_key(|&&k| Reverse(k))
+        .take(3)
+        .copied()
+        .map(u64::from)
+        .sum()
 }
 
-fn parse_and_sum(s: &str) -> Result<u32, ParseIntError> {
-    s.lines().map(str::parse::<u32>).sum()
+fn parse(input: &str) -> Result<Vec<u32>, anyhow::Error> {
+    blocks(input).map(parse_and_sum).collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(include_str!("../resources/example01.txt"))?, 24000);
-        Ok(())
-    }
+fn parse_and_sum(s: &str) -> Result<u32, anyhow::Error> {
+    s.lines().try_fold(0u32, |total, l| {
+        total
+            .checked_add(l.parse()?)
+            .ok_or_else(|| anyhow!("An elf is carrying too many calories to count"))
+    })
+}
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(include_str!("../resources/example01.txt"))?, 45000);
-        Ok(())
-    }
+/// `size` elves, each carrying a handful of snacks
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            (0..rng.range(1..=15))
+                .map(|_| rng.range(1_000..=60_000_u32))
+                .join("\n")
+        })
+        .join("\n\n")
 }
@@ -1,17 +1,17 @@
-use itertools::{process_results, Itertools};
+use itertools::Itertools;
 
 use self::parser::parse;
 
-const INPUT: &str = include_str!("../resources/input02.txt");
+use crate::{generate::Rng, Answer, Solution};
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Play {
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Play {
     Rock = 1,     // A
     Paper = 2,    // B
     Scissors = 3, // C
 }
 impl Play {
-    pub fn outcome(self, op: Self) -> Outcome {
+    fn outcome(self, op: Self) -> Outcome {
         if self == op {
             Outcome::Draw
         } else if self.win() == op {
@@ -21,7 +21,7 @@ impl Play {
         }
     }
 
-    pub const fn win(self) -> Self {
+    const fn win(self) -> Self {
         match self {
             Self::Rock => Self::Scissors,
             Self::Paper => Self::Rock,
@@ -29,7 +29,7 @@ impl Play {
         }
     }
 
-    pub const fn lose(self) -> Self {
+    const fn lose(self) -> Self {
         match self {
             Self::Rock => Self::Paper,
             Self::Paper => Self::Scissors,
@@ -38,8 +38,8 @@ impl Play {
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Recommended {
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Recommended {
     X,
     Y,
     Z,
@@ -52,28 +52,37 @@ enum Outcome {
     Win = 6,
 }
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day02;
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
-}
+impl Solution for Day02 {
+    const DAY: u32 = 2;
+    const TITLE: &'static str = "Rock Paper Scissors";
+
+    type Input<'a> = Vec<(Play, Recommended)>;
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let answer = process_results(input.lines().map(parse).map_ok(play_recommended), |i| {
-        i.sum()
-    })?;
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input).into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input).into())
+    }
 
-    Ok(answer)
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let answer = process_results(input.lines().map(parse).map_ok(play_for_outcome), |i| {
-        i.sum()
-    })?;
+fn part_a(rounds: &[(Play, Recommended)]) -> u32 {
+    rounds.iter().copied().map(play_recommended).sum()
+}
 
-    Ok(answer)
+fn part_b(rounds: &[(Play, Recommended)]) -> u32 {
+    rounds.iter().copied().map(play_for_outcome).sum()
 }
 
 fn play_recommended((op, rec): (Play, Recommended)) -> u32 {
@@ -94,11 +103,25 @@ const fn play_for_outcome((op, rec): (Play, Recommended)) -> u32 {
     (rec_play as u32) + (rec_outcome as u32)
 }
 
+/// `size` rounds of the strategy guide
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}",
+                char::from(rng.range(b'A'..=b'C')),
+                char::from(rng.range(b'X'..=b'Z'))
+            )
+        })
+        .join("\n")
+}
+
 mod parser {
     use nom::branch::alt;
     use nom::bytes::complete::tag;
-    use nom::character::complete::char;
+    use nom::character::complete::{char, line_ending};
     use nom::combinator::value;
+    use nom::multi::separated_list1;
     use nom::sequence::separated_pair;
     use nom::IResult;
 
@@ -107,8 +130,8 @@ mod parser {
     use super::Play;
     use super::Recommended;
 
-    pub(super) fn parse(input: &str) -> Result<(Play, Recommended), anyhow::Error> {
-        final_parser(line)(input)
+    pub(super) fn parse(input: &str) -> Result<Vec<(Play, Recommended)>, anyhow::Error> {
+        final_parser(separated_list1(line_ending, line))(input)
     }
 
     fn line(input: &str) -> IResult<&str, (Play, Recommended)> {
@@ -130,21 +153,3 @@ mod parser {
         ))(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example02.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 15);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 12);
-        Ok(())
-    }
-}
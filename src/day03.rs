@@ -1,80 +1,163 @@
 use anyhow::anyhow;
 use itertools::{process_results, Itertools};
 
-const INPUT: &str = include_str!("../resources/input03.txt");
+use crate::{generate::Rng, Answer, Solution};
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u32 = 3;
+    const TITLE: &'static str = "Rucksack Reorganization";
+
+    /// The priority of each item in each rucksack, counting from 0
+    type Input<'a> = Vec<Vec<u32>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        input
+            .lines()
+            .map(|l| l.chars().map(char_idx).collect())
+            .collect()
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    process_results(input.lines().map(find_first_common_between_halves), |i| {
-        i.sum()
-    })
+fn part_a(rucksacks: &[Vec<u32>]) -> Result<u32, anyhow::Error> {
+    process_results(
+        rucksacks
+            .iter()
+            .map(|r| find_first_common_between_halves(r)),
+        |i| i.sum(),
+    )
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
+fn part_b(rucksacks: &[Vec<u32>]) -> Result<u32, anyhow::Error> {
     #[allow(clippy::redundant_closure_for_method_calls)]
     process_results(
-        input.lines().tuples().map(find_first_common_to_three),
+        rucksacks.iter().tuples().map(find_first_common_to_three),
         |i| i.sum(),
     )
 }
 
-fn find_first_common_between_halves(l: &str) -> Result<u32, anyhow::Error> {
-    let bitmap = str_to_bitmap(&l[..l.len() / 2])?;
-    find_first_common(&l[l.len() / 2..], bitmap)
+fn find_first_common_between_halves(items: &[u32]) -> Result<u32, anyhow::Error> {
+    let (first, second) = items.split_at(items.len() / 2);
+    find_first_common(second, to_bitmap(first))
 }
 
-fn find_first_common_to_three((a, b, c): (&str, &str, &str)) -> Result<u32, anyhow::Error> {
-    let bitmap = str_to_bitmap(a)? & str_to_bitmap(b)?;
-    find_first_common(c, bitmap)
+fn find_first_common_to_three(
+    (a, b, c): (&Vec<u32>, &Vec<u32>, &Vec<u32>),
+) -> Result<u32, anyhow::Error> {
+    find_first_common(c, to_bitmap(a) & to_bitmap(b))
 }
 
-fn find_first_common(s: &str, bitmap: u64) -> Result<u32, anyhow::Error> {
-    for c in s.chars() {
-        let char_idx = char_idx(c)?;
-        if bitmap & (1u64 << char_idx) > 0 {
-            return Ok(char_idx + 1);
-        }
-    }
-    Err(anyhow!("No common item found"))
+fn find_first_common(items: &[u32], bitmap: u64) -> Result<u32, anyhow::Error> {
+    items
+        .iter()
+        .find(|&&i| bitmap & (1u64 << i) > 0)
+        .map(|i| i + 1)
+        .ok_or_else(|| anyhow!("No common item found"))
 }
 
-fn str_to_bitmap(l: &str) -> Result<u64, anyhow::Error> {
-    l.chars()
-        .map(char_idx)
-        .fold_ok(0u64, |acc, i| acc | (1u64 << i))
+fn to_bitmap(items: &[u32]) -> u64 {
+    items.iter().fold(0u64, |acc, i| acc | (1u64 << i))
 }
 
 fn char_idx(c: char) -> Result<u32, anyhow::Error> {
-    if ('a'..='z').contains(&c) {
+    if c.is_ascii_lowercase() {
         Ok(c as u32 - 'a' as u32)
-    } else if ('A'..='Z').contains(&c) {
+    } else if c.is_ascii_uppercase() {
         Ok(c as u32 - 'A' as u32 + 26)
     } else {
         Err(anyhow!("char out of range"))
     }
 }
 
+/// `size` groups of three elves, each group sharing a badge and each rucksack holding one item in
+/// both compartments
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let items = ('a'..='z').chain('A'..='Z').collect_vec();
+    let item = |rng: &mut Rng| rng.choose(&items).copied().unwrap_or('a');
+
+    let mut rucksacks = Vec::with_capacity(size * 3);
+    for _ in 0..size {
+        let badge = item(rng);
+        for _ in 0..3 {
+            let half = rng.range(2..=16_usize);
+            let mut contents = (0..half * 2).map(|_| item(rng)).collect_vec();
+            let common = rng.range(0..=half - 1);
+            contents[half + rng.range(0..=half - 1)] = contents[common];
+            // Skip over the common item so the badge can't replace it
+            let badge_at = rng.range(0..=half - 2);
+            contents[badge_at + usize::from(badge_at >= common)] = badge;
+            rucksacks.push(String::from_iter(contents));
+        }
+    }
+    rucksacks.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example03.txt");
 
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 157);
-        Ok(())
+    fn priority(c: char) -> u32 {
+        ('a'..='z')
+            .chain('A'..='Z')
+            .zip(1..)
+            .find(|&(l, _)| l == c)
+            .map_or(0, |(_, p)| p)
     }
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 70);
-        Ok(())
+    /// A rucksack with the same number of items in each compartment
+    fn rucksack() -> impl Strategy<Value = String> {
+        let letters = ('a'..='z').chain('A'..='Z').collect_vec();
+        (1..12_usize).prop_flat_map(move |n| {
+            prop::collection::vec(prop::sample::select(letters.clone()), 2 * n)
+                .prop_map(String::from_iter)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn part_a_matches_brute_force(rucksacks in prop::collection::vec(rucksack(), 1..10)) {
+            let parsed = Day03::parse(&rucksacks.join("\n"))
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let expected: Option<u32> = rucksacks
+                .iter()
+                .map(|r| {
+                    let (first, second) = r.split_at(r.len() / 2);
+                    second.chars().find(|&c| first.contains(c)).map(priority)
+                })
+                .sum();
+
+            prop_assert_eq!(part_a(&parsed).ok(), expected);
+        }
+
+        #[test]
+        fn part_b_matches_brute_force(rucksacks in prop::collection::vec(rucksack(), 3..12)) {
+            let parsed = Day03::parse(&rucksacks.join("\n"))
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let expected: Option<u32> = rucksacks
+                .chunks_exact(3)
+                .map(|g| {
+                    g[2].chars()
+                        .find(|&c| g[0].contains(c) && g[1].contains(c))
+                        .map(priority)
+                })
+                .sum();
+
+            prop_assert_eq!(part_b(&parsed).ok(), expected);
+        }
     }
 }
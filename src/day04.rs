@@ -1,57 +1,84 @@
-use crate::day04::parser::parse;
+use crate::{day04::parser::parse, generate::Rng, Answer, Solution};
+use itertools::Itertools;
 use std::ops::RangeInclusive;
 
-use itertools::{process_results, Itertools};
+/// The sections assigned to each elf of a pair
+pub type Assignment = (RangeInclusive<u32>, RangeInclusive<u32>);
 
-const INPUT: &str = include_str!("../resources/input04.txt");
+pub struct Day04;
 
-pub fn solve_part_a() -> Result<usize, anyhow::Error> {
-    part_a(INPUT)
-}
+impl Solution for Day04 {
+    const DAY: u32 = 4;
+    const TITLE: &'static str = "Camp Cleanup";
+
+    /// The pair of section ranges assigned to each pair of elves
+    type Input<'a> = Vec<Assignment>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input).into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input).into())
+    }
 
-pub fn solve_part_b() -> Result<usize, anyhow::Error> {
-    part_b(INPUT)
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<usize, anyhow::Error> {
-    #[allow(clippy::redundant_closure_for_method_calls)]
-    process_results(input.lines().map(parse).filter_ok(contains), |i| i.count())
+fn part_a(pairs: &[Assignment]) -> usize {
+    pairs.iter().filter(|p| contains(p)).count()
 }
 
-fn part_b(input: &str) -> Result<usize, anyhow::Error> {
-    #[allow(clippy::redundant_closure_for_method_calls)]
-    process_results(input.lines().map(parse).filter_ok(overlaps), |i| i.count())
+fn part_b(pairs: &[Assignment]) -> usize {
+    pairs.iter().filter(|p| overlaps(p)).count()
 }
 
-fn contains((a, b): &(RangeInclusive<u32>, RangeInclusive<u32>)) -> bool {
-    if a.end() - a.start() > b.end() - b.start() {
-        a.start() <= b.start() && a.end() >= b.end()
-    } else {
-        b.start() <= a.start() && b.end() >= a.end()
-    }
+fn contains((a, b): &Assignment) -> bool {
+    (a.start() <= b.start() && a.end() >= b.end()) || (b.start() <= a.start() && b.end() >= a.end())
 }
 
-fn overlaps((a, b): &(RangeInclusive<u32>, RangeInclusive<u32>)) -> bool {
+fn overlaps((a, b): &Assignment) -> bool {
     a.end() >= b.start() && a.start() <= b.end()
 }
 
+/// `size` pairs of elves, each assigned a range of sections between 1 and 99
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let range = |rng: &mut Rng| {
+        let start = rng.range(1..=99_u32);
+        format!("{start}-{}", rng.range(start..=99))
+    };
+    (0..size)
+        .map(|_| format!("{},{}", range(rng), range(rng)))
+        .join("\n")
+}
+
 mod parser {
     use std::ops::RangeInclusive;
 
     use nom::{
-        bytes::complete::tag, character::complete::u32, combinator::map, sequence::separated_pair,
+        bytes::complete::tag,
+        character::complete::{line_ending, u32},
+        combinator::map,
+        multi::separated_list1,
+        sequence::separated_pair,
         IResult,
     };
 
     use crate::final_parser;
 
-    pub(super) fn parse(
-        input: &str,
-    ) -> Result<(RangeInclusive<u32>, RangeInclusive<u32>), anyhow::Error> {
-        final_parser(line)(input)
+    use super::Assignment;
+
+    pub(super) fn parse(input: &str) -> Result<Vec<Assignment>, anyhow::Error> {
+        final_parser(separated_list1(line_ending, line))(input)
     }
 
-    fn line(input: &str) -> IResult<&str, (RangeInclusive<u32>, RangeInclusive<u32>)> {
+    fn line(input: &str) -> IResult<&str, Assignment> {
         separated_pair(range, tag(","), range)(input)
     }
 
@@ -61,21 +88,3 @@ mod parser {
         })(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example04.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 2);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 4);
-        Ok(())
-    }
-}
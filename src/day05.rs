@@ -1,15 +1,15 @@
 use crate::{
     day05::parser::{parse_instructions, parse_locations},
-    DOUBLE_LINE_ENDING,
+    generate::Rng,
+    inputs::blocks,
+    within, Answer, Solution,
 };
 
 use anyhow::anyhow;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../resources/input05.txt");
-
 #[derive(Debug, Clone, Copy)]
-struct Crate(char);
+pub struct Crate(char);
 impl From<Crate> for char {
     fn from(c: Crate) -> Self {
         c.0
@@ -17,24 +17,48 @@ impl From<Crate> for char {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Instruction {
-    pub count: u32,
-    pub from: u32,
-    pub to: u32,
+pub struct Instruction {
+    count: u32,
+    from: u32,
+    to: u32,
 }
 
-pub fn solve_part_a() -> Result<String, anyhow::Error> {
-    part_a(INPUT)
+/// The starting stacks of crates, bottom first, and the moves to make
+#[derive(Debug, Clone)]
+pub struct Supplies {
+    stacks: Vec<Vec<Crate>>,
+    instructions: Vec<Instruction>,
 }
 
-pub fn solve_part_b() -> Result<String, anyhow::Error> {
-    part_b(INPUT)
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u32 = 5;
+    const TITLE: &'static str = "Supply Stacks";
+
+    type Input<'a> = Supplies;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse_input(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<String, anyhow::Error> {
-    let (mut locations, instructions) = parse_input(input)?;
+fn part_a(supplies: &Supplies) -> Result<String, anyhow::Error> {
+    let mut locations = supplies.stacks.clone();
 
-    for i in instructions {
+    for i in &supplies.instructions {
         for _ in 0..i.count {
             let c = locations[i.from as usize - 1]
                 .pop()
@@ -46,12 +70,16 @@ fn part_a(input: &str) -> Result<String, anyhow::Error> {
     Ok(read_top_crates(locations))
 }
 
-fn part_b(input: &str) -> Result<String, anyhow::Error> {
-    let (mut locations, instructions) = parse_input(input)?;
+fn part_b(supplies: &Supplies) -> Result<String, anyhow::Error> {
+    let mut locations = supplies.stacks.clone();
 
-    for i in instructions {
+    for i in &supplies.instructions {
         let from = &mut locations[i.from as usize - 1];
-        let moving_crates = from.drain(from.len() - i.count as usize..).collect_vec();
+        let start = from
+            .len()
+            .checked_sub(i.count as usize)
+            .ok_or_else(|| anyhow!("Tried to move more crates than the stack holds"))?;
+        let moving_crates = from.drain(start..).collect_vec();
         locations[i.to as usize - 1].extend(moving_crates);
     }
 
@@ -61,20 +89,77 @@ fn part_b(input: &str) -> Result<String, anyhow::Error> {
 fn read_top_crates(locations: Vec<Vec<Crate>>) -> String {
     locations
         .into_iter()
-        .map(|l| Into::<char>::into(l[l.len() - 1]))
+        .filter_map(|l| l.last().copied().map(Into::<char>::into))
         .collect()
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Vec<Crate>>, Vec<Instruction>), anyhow::Error> {
-    let (locations_input, instructions_input) = input
-        .split(DOUBLE_LINE_ENDING)
+fn parse_input(input: &str) -> Result<Supplies, anyhow::Error> {
+    let (locations_input, instructions_input) = blocks(input)
         .next_tuple()
         .ok_or_else(|| anyhow!("unable to read input and location section from input"))?;
 
-    Ok((
-        parse_locations(locations_input)?,
-        parse_instructions(instructions_input)?,
-    ))
+    let stacks = parse_locations(locations_input).map_err(within(input, locations_input))?;
+    let instructions =
+        parse_instructions(instructions_input).map_err(within(input, instructions_input))?;
+
+    let crates = stacks.iter().map(Vec::len).sum::<usize>();
+    if let Some(i) = instructions.iter().find(|i| i.count as usize > crates) {
+        return Err(anyhow!(
+            "Can't move {} crates, there are only {crates}",
+            i.count
+        ));
+    }
+
+    let stack_numbers = 1..=stacks.len();
+    if let Some(i) = instructions.iter().find(|i| {
+        !stack_numbers.contains(&(i.from as usize)) || !stack_numbers.contains(&(i.to as usize))
+    }) {
+        return Err(anyhow!(
+            "Can't move from stack {} to stack {}, there are {} stacks",
+            i.from,
+            i.to,
+            stacks.len()
+        ));
+    }
+
+    Ok(Supplies {
+        stacks,
+        instructions,
+    })
+}
+
+/// Nine stacks of crates and `size` moves between them, each only moving crates that are there
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut heights = (0..9).map(|_| rng.range(1..=8_usize)).collect_vec();
+    let tallest = heights.iter().copied().max().unwrap_or(0);
+    let mut lines = (0..tallest)
+        .rev()
+        .map(|level| {
+            heights
+                .iter()
+                .map(|&h| {
+                    if h > level {
+                        format!("[{}]", char::from(rng.range(b'A'..=b'Z')))
+                    } else {
+                        "   ".to_owned()
+                    }
+                })
+                .join(" ")
+        })
+        .collect_vec();
+    lines.push((1..=heights.len()).map(|n| format!(" {n} ")).join(" "));
+    lines.push(String::new());
+
+    for _ in 0..size {
+        let stacked = (0..heights.len()).filter(|&s| heights[s] > 0).collect_vec();
+        let from = rng.choose(&stacked).copied().unwrap_or(0);
+        let to = (from + rng.range(1..=heights.len() - 1)) % heights.len();
+        let count = rng.range(1..=heights[from]);
+        heights[from] -= count;
+        heights[to] += count;
+        lines.push(format!("move {count} from {} to {}", from + 1, to + 1));
+    }
+    lines.join("\n")
 }
 
 mod parser {
@@ -89,7 +174,7 @@ mod parser {
     use nom::AsChar;
     use nom::{bytes::complete::tag, sequence::delimited, IResult};
 
-    use crate::final_parser;
+    use crate::{final_parser, within};
 
     use super::{Crate, Instruction};
 
@@ -102,17 +187,21 @@ mod parser {
 
         let mut stacks = Vec::new();
         stacks.extend(
-            final_parser(stack_numbers)(stack_numbers_input)?
+            final_parser(stack_numbers)(stack_numbers_input)
+                .map_err(within(input, stack_numbers_input))?
                 .into_iter()
                 .map(|_| Vec::new()),
         );
 
         for line in lines {
-            let crates = final_parser(crates)(line)?;
+            let crates = final_parser(crates)(line).map_err(within(input, line))?;
 
             for c in crates.into_iter().enumerate() {
                 if let (i, Some(c)) = c {
-                    stacks[i].push(c);
+                    stacks
+                        .get_mut(i)
+                        .ok_or_else(|| anyhow!("Crate {} is beyond the last stack", i + 1))?
+                        .push(c);
                 }
             }
         }
@@ -153,18 +242,28 @@ mod parser {
 
 #[cfg(test)]
 mod tests {
+    use crate::ParseError;
+
     use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example05.txt");
 
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), "CMZ");
-        Ok(())
+    fn parse_error(input: &str) -> Result<ParseError, anyhow::Error> {
+        parse_input(input)
+            .err()
+            .ok_or_else(|| anyhow!("parsed"))?
+            .downcast::<ParseError>()
     }
 
     #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), "MCD");
+    fn locates_errors_against_the_whole_input() -> Result<(), anyhow::Error> {
+        let crates = "    [D]\n[N] [C]\n 1   2 \n\n";
+
+        let e = parse_error(&format!("{crates}move 1 from 2 to 1\nmove x from 1 to 2\n"))?;
+        assert_eq!((e.line, e.column), (6, 6));
+        assert_eq!(e.text, "move x from 1 to 2");
+
+        let e = parse_error("    [D]\n[N] {C}\n 1   2 \n\nmove 1 from 2 to 1\n")?;
+        assert_eq!((e.line, e.column), (2, 5));
+        assert_eq!(e.text, "[N] {C}");
         Ok(())
     }
 }
@@ -1,21 +1,62 @@
-use std::collections::VecDeque;
+use std::{collections::VecDeque, ops::RangeInclusive};
 
 use anyhow::anyhow;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../resources/input06.txt");
+use crate::{generate::Rng, Alternative, Answer, Part, Solution};
 
-pub fn solve_part_a() -> Result<usize, anyhow::Error> {
-    part_a(INPUT)
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u32 = 6;
+    const TITLE: &'static str = "Tuning Trouble";
+    // The marker alone takes 14 characters
+    const GENERATE_SIZES: RangeInclusive<usize> = 14..=1_000_000;
+
+    /// The datastream, one lowercase letter per byte
+    type Input<'a> = &'a [u8];
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    const ALTERNATIVES: &'static [Alternative<Self>] = &[
+        Alternative {
+            name: "deque",
+            part: Part::A,
+            solve: |input| Ok(deque(input, 4)?.into()),
+        },
+        Alternative {
+            name: "windows",
+            part: Part::B,
+            solve: |input| Ok(windows(input, 14)?.into()),
+        },
+    ];
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        generate(size, rng)
+    }
 }
 
-pub fn solve_part_b() -> Result<usize, anyhow::Error> {
-    part_b(INPUT)
+fn parse(input: &str) -> Result<&[u8], anyhow::Error> {
+    if input.bytes().all(|b| b.is_ascii_lowercase()) {
+        Ok(input.as_bytes())
+    } else {
+        Err(anyhow!("datastream should only contain lowercase letters"))
+    }
 }
 
-fn part_a(input: &str) -> Result<usize, anyhow::Error> {
-    input
-        .chars()
+fn part_a(signal: &[u8]) -> Result<usize, anyhow::Error> {
+    signal
+        .iter()
         .tuple_windows()
         .enumerate()
         .find(|(_, (a, b, c, d))| [a, b, c, d].iter().all_unique())
@@ -23,34 +64,47 @@ fn part_a(input: &str) -> Result<usize, anyhow::Error> {
         .ok_or_else(|| anyhow!("No range found"))
 }
 
-fn part_b(input: &str) -> Result<usize, anyhow::Error> {
+fn part_b(signal: &[u8]) -> Result<usize, anyhow::Error> {
+    deque(signal, 14)
+}
+
+/// How far into the signal the first `len` different characters in a row end, keeping the last
+/// `len` characters in a queue
+fn deque(signal: &[u8], len: usize) -> Result<usize, anyhow::Error> {
     let mut buff = VecDeque::new();
-    for (i, c) in input.chars().enumerate() {
+    for (i, c) in signal.iter().enumerate() {
         buff.push_back(c);
 
-        if buff.len() > 14 {
+        if buff.len() > len {
             buff.pop_front();
         }
-        if buff.len() == 14 && buff.iter().all_unique() {
+        if buff.len() == len && buff.iter().all_unique() {
             return Ok(i + 1);
         }
     }
     Err(anyhow!("No range found"))
 }
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example06.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 7);
-        Ok(())
-    }
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 19);
-        Ok(())
-    }
+/// How far into the signal the first `len` different characters in a row end, checking every
+/// slice of that length
+fn windows(signal: &[u8], len: usize) -> Result<usize, anyhow::Error> {
+    signal
+        .windows(len)
+        .position(|w| w.iter().all_unique())
+        .map(|i| i + len)
+        .ok_or_else(|| anyhow!("No range found"))
+}
+
+/// A signal `size` characters long with its only start-of-message marker at the very end
+fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+    let noise = size
+        .checked_sub(14)
+        .ok_or_else(|| anyhow!("A signal needs at least 14 characters to hold a marker"))?;
+    let mut marker = ('d'..='z').collect_vec();
+    rng.shuffle(&mut marker);
+
+    Ok((0..noise)
+        .map(|_| char::from(rng.range(b'a'..=b'c')))
+        .chain(marker.into_iter().take(14))
+        .collect())
 }
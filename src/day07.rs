@@ -1,53 +1,74 @@
 use anyhow::anyhow;
+use itertools::Itertools;
 
 use self::parser::parse;
 
-const INPUT: &str = include_str!("../resources/input07.txt");
+use crate::{generate::Rng, Answer, Solution};
 
 #[derive(Debug)]
-enum TerminalLine<'a> {
+pub enum TerminalLine<'a> {
     ChangeDirectory(&'a str),
     List(Vec<ListLine<'a>>),
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
-enum ListLine<'a> {
+pub enum ListLine<'a> {
     File(u32, &'a str),
     Directory(&'a str),
 }
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u32 = 7;
+    const TITLE: &'static str = "No Space Left On Device";
+
+    type Input<'a> = Vec<TerminalLine<'a>>;
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let sizes = get_dir_sizes(input)?;
+fn part_a(terminal: &[TerminalLine<'_>]) -> Result<u64, anyhow::Error> {
+    let sizes = get_dir_sizes(terminal)?;
 
     Ok(sizes.into_iter().filter(|&s| s <= 100_000).sum())
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let sizes = get_dir_sizes(input)?;
+fn part_b(terminal: &[TerminalLine<'_>]) -> Result<u64, anyhow::Error> {
+    let sizes = get_dir_sizes(terminal)?;
 
-    let to_free = sizes[sizes.len() - 1] - 40_000_000;
+    let to_free = sizes[sizes.len() - 1]
+        .checked_sub(40_000_000)
+        .ok_or_else(|| anyhow!("There is already enough free space"))?;
 
     Ok(sizes
         .into_iter()
-        .fold(u32::MAX, |a, b| if b > to_free { a.min(b) } else { a }))
+        .fold(u64::MAX, |a, b| if b >= to_free { a.min(b) } else { a }))
 }
 
-fn get_dir_sizes(input: &str) -> Result<Vec<u32>, anyhow::Error> {
-    let lines = parse(input)?;
+/// The total size of every directory, wide enough that adding up `u32` file sizes can't overflow
+fn get_dir_sizes(terminal: &[TerminalLine<'_>]) -> Result<Vec<u64>, anyhow::Error> {
     let mut sizes = Vec::new();
     let mut current_total = 0;
     let mut total_stack = Vec::new();
-    for l in lines {
+    for l in terminal {
         match l {
-            TerminalLine::ChangeDirectory(d) => match d {
+            TerminalLine::ChangeDirectory(d) => match *d {
                 "/" => {
                     //slightly naughty assumption that this is only present at the start of the input
                 }
@@ -72,23 +93,72 @@ fn get_dir_sizes(input: &str) -> Result<Vec<u32>, anyhow::Error> {
     sizes.push(current_total);
 
     //and unwind the stack back to the root
-    for t in total_stack {
+    for t in total_stack.into_iter().rev() {
         current_total += t;
         sizes.push(current_total);
     }
     Ok(sizes)
 }
 
-fn file_size_sum(list_items: Vec<ListLine>) -> u32 {
+fn file_size_sum(list_items: &[ListLine<'_>]) -> u64 {
     list_items
-        .into_iter()
+        .iter()
         .map(|i| match i {
-            ListLine::File(s, _) => s,
+            ListLine::File(s, _) => u64::from(*s),
             ListLine::Directory(_) => 0,
         })
         .sum()
 }
 
+/// A terminal session exploring `size` directories, holding more than 40,000,000 in total so
+/// that something has to be deleted
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut children = vec![Vec::new(); size];
+    for d in 1..size {
+        children[rng.range(0..=d - 1)].push(d);
+    }
+
+    // Every listing needs at least one entry
+    let counts = children
+        .iter()
+        .map(|c| rng.range(usize::from(c.is_empty())..=3))
+        .collect_vec();
+    let cap = (50_000_000 / counts.iter().sum::<usize>().max(1)).clamp(1, 300_000);
+    let mut files = counts
+        .iter()
+        .map(|&n| (0..n).map(|_| rng.range(1..=cap)).collect_vec())
+        .collect_vec();
+    let total: usize = files.iter().flatten().sum();
+    if total <= 40_000_000 {
+        files[0].push(40_000_001 - total + rng.range(0..=20_000_000));
+    }
+
+    let mut lines = vec!["$ cd /".to_owned()];
+    write_listing(0, &children, &files, &mut lines);
+    lines.join("\n")
+}
+
+fn write_listing(
+    dir: usize,
+    children: &[Vec<usize>],
+    files: &[Vec<usize>],
+    lines: &mut Vec<String>,
+) {
+    lines.push("$ ls".to_owned());
+    lines.extend(children[dir].iter().map(|c| format!("dir d{c}")));
+    lines.extend(
+        files[dir]
+            .iter()
+            .enumerate()
+            .map(|(i, size)| format!("{size} f{i}.dat")),
+    );
+    for &c in &children[dir] {
+        lines.push(format!("$ cd d{c}"));
+        write_listing(c, children, files, lines);
+        lines.push("$ cd ..".to_owned());
+    }
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -104,11 +174,11 @@ mod parser {
 
     use super::{ListLine, TerminalLine};
 
-    pub(super) fn parse(input: &str) -> Result<Vec<TerminalLine>, anyhow::Error> {
+    pub(super) fn parse(input: &str) -> Result<Vec<TerminalLine<'_>>, anyhow::Error> {
         final_parser(separated_list1(line_ending, terminal_line))(input)
     }
 
-    fn terminal_line(input: &str) -> IResult<&str, TerminalLine> {
+    fn terminal_line(input: &str) -> IResult<&str, TerminalLine<'_>> {
         alt((
             map(change_directory, TerminalLine::ChangeDirectory),
             map(list, TerminalLine::List),
@@ -119,7 +189,7 @@ mod parser {
         preceded(tag("$ cd "), not_line_ending)(input)
     }
 
-    fn list(input: &str) -> IResult<&str, Vec<ListLine>> {
+    fn list(input: &str) -> IResult<&str, Vec<ListLine<'_>>> {
         preceded(
             terminated(tag("$ ls"), line_ending),
             separated_list1(
@@ -142,18 +212,98 @@ mod parser {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example07.txt");
 
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 95437);
-        Ok(())
+    #[derive(Debug, Clone)]
+    struct Dir {
+        files: Vec<u32>,
+        dirs: Vec<Self>,
     }
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 24933642);
-        Ok(())
+    impl Dir {
+        fn size(&self) -> u64 {
+            self.files.iter().copied().map(u64::from).sum::<u64>()
+                + self.dirs.iter().map(Self::size).sum::<u64>()
+        }
+
+        fn all_sizes(&self, sizes: &mut Vec<u64>) {
+            sizes.push(self.size());
+            for d in &self.dirs {
+                d.all_sizes(sizes);
+            }
+        }
+
+        /// Writes out the session of a `cd` into every directory and an `ls` of each
+        fn terminal(&self, name: &str, lines: &mut Vec<String>) {
+            lines.push(format!("$ cd {name}"));
+            lines.push("$ ls".to_owned());
+            for i in 0..self.dirs.len() {
+                lines.push(format!("dir d{i}"));
+            }
+            for (i, size) in self.files.iter().enumerate() {
+                lines.push(format!("{size} f{i}.txt"));
+            }
+            for (i, d) in self.dirs.iter().enumerate() {
+                d.terminal(&format!("d{i}"), lines);
+                lines.push("$ cd ..".to_owned());
+            }
+        }
+    }
+
+    /// A directory tree with a mix of small and very large files, so both parts have something
+    /// to find
+    fn tree() -> impl Strategy<Value = Dir> {
+        let files =
+            prop::collection::vec(prop_oneof![1..50_000_u32, 1_000_000..15_000_000_u32], 1..4);
+        let leaf = files.clone().prop_map(|files| Dir {
+            files,
+            dirs: Vec::new(),
+        });
+        leaf.prop_recursive(4, 24, 4, move |inner| {
+            (files.clone(), prop::collection::vec(inner, 0..4))
+                .prop_map(|(files, dirs)| Dir { files, dirs })
+        })
+    }
+
+    /// The session as the puzzle shows it, which never climbs back out of the last directory
+    fn terminal(root: &Dir) -> String {
+        let mut lines = Vec::new();
+        root.terminal("/", &mut lines);
+        while lines.last().is_some_and(|l| l == "$ cd ..") {
+            lines.pop();
+        }
+        lines.join("\n")
+    }
+
+    proptest! {
+        #[test]
+        fn part_a_matches_tree(root in tree()) {
+            let input = terminal(&root);
+            let lines = parse(&input).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let mut sizes = Vec::new();
+            root.all_sizes(&mut sizes);
+
+            prop_assert_eq!(
+                part_a(&lines).map_err(|e| TestCaseError::fail(e.to_string()))?,
+                sizes.into_iter().filter(|&s| s <= 100_000).sum::<u64>()
+            );
+        }
+
+        #[test]
+        fn part_b_matches_tree(root in tree()) {
+            prop_assume!(root.size() > 40_000_000);
+            let input = terminal(&root);
+            let lines = parse(&input).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let mut sizes = Vec::new();
+            root.all_sizes(&mut sizes);
+            let to_free = root.size() - 40_000_000;
+
+            prop_assert_eq!(
+                part_b(&lines).map_err(|e| TestCaseError::fail(e.to_string()))?,
+                sizes.into_iter().filter(|&s| s >= to_free).min().unwrap_or(0)
+            );
+        }
     }
 }
@@ -1,46 +1,85 @@
-// use anyhow::anyhow;
+use std::ops::RangeInclusive;
 
+use anyhow::anyhow;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../resources/input08.txt");
+use crate::{generate::Rng, Answer, Solution};
 
-#[must_use]
-pub fn solve_part_a() -> usize {
-    part_a(INPUT)
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u32 = 8;
+    const TITLE: &'static str = "Treetop Tree House";
+    // The forest is square, so grows with the size squared
+    const GENERATE_SIZES: RangeInclusive<usize> = 1..=5_000;
+
+    /// The height of each tree, row by row
+    type Input<'a> = Vec<Vec<char>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-#[must_use]
-pub fn solve_part_b() -> usize {
-    part_b(INPUT)
+fn parse(input: &str) -> Result<Vec<Vec<char>>, anyhow::Error> {
+    let trees = input.lines().map(|s| s.chars().collect_vec()).collect_vec();
+    let width = trees
+        .first()
+        .map(Vec::len)
+        .ok_or_else(|| anyhow!("No trees found"))?;
+
+    if trees
+        .iter()
+        .all(|r| r.len() == width && r.iter().all(char::is_ascii_digit))
+    {
+        Ok(trees)
+    } else {
+        Err(anyhow!("Trees should be a rectangle of digits"))
+    }
 }
 
-fn part_a(input: &str) -> usize {
-    let mut trees = input
-        .lines()
+fn part_a(grid: &[Vec<char>]) -> Result<usize, anyhow::Error> {
+    let mut trees = grid
+        .iter()
         .with_position()
         .map(|l| match l {
             itertools::Position::Middle(s) => s
-                .chars()
+                .iter()
                 .with_position()
                 .map(|p| match p {
-                    itertools::Position::Middle(c) => (c, false),
-                    itertools::Position::First(c)
-                    | itertools::Position::Last(c)
-                    | itertools::Position::Only(c) => (c, true),
+                    itertools::Position::Middle(&c) => (c, false),
+                    itertools::Position::First(&c)
+                    | itertools::Position::Last(&c)
+                    | itertools::Position::Only(&c) => (c, true),
                 })
                 .collect_vec(),
             itertools::Position::First(s)
             | itertools::Position::Last(s)
-            | itertools::Position::Only(s) => s.chars().map(|c| (c, true)).collect_vec(),
+            | itertools::Position::Only(s) => s.iter().map(|&c| (c, true)).collect_vec(),
         })
         .collect_vec();
 
     let height = trees.len();
-    let width = trees[0].len();
+    let width = trees
+        .first()
+        .map(Vec::len)
+        .ok_or_else(|| anyhow!("No trees found"))?;
 
     //left to right
     #[allow(clippy::needless_range_loop)]
-    for y in 1..=(height - 2) {
+    for y in 1..height.saturating_sub(1) {
         let (mut highest_vis, _) = trees[y][0];
         for x in 1..(width - 1) {
             let (h, _) = trees[y][x];
@@ -53,7 +92,7 @@ fn part_a(input: &str) -> usize {
 
     //right to left
     #[allow(clippy::needless_range_loop)]
-    for y in 1..=(height - 2) {
+    for y in 1..height.saturating_sub(1) {
         let (mut highest_vis, _) = trees[y][width - 1];
         for x in (1..(width - 1)).rev() {
             let (h, _) = trees[y][x];
@@ -65,6 +104,7 @@ fn part_a(input: &str) -> usize {
     }
 
     //top to bottom
+    #[allow(clippy::needless_range_loop)]
     for x in 1..(width - 1) {
         let (mut highest_vis, _) = trees[0][x];
 
@@ -79,6 +119,7 @@ fn part_a(input: &str) -> usize {
     }
 
     //bottom to top
+    #[allow(clippy::needless_range_loop)]
     for x in 1..(width - 1) {
         let (mut highest_vis, _) = trees[height - 1][x];
         #[allow(clippy::needless_range_loop)]
@@ -91,31 +132,33 @@ fn part_a(input: &str) -> usize {
         }
     }
 
-    trees
+    Ok(trees
         .into_iter()
         .map(|r| r.iter().filter(|(_, v)| *v).count())
-        .sum()
+        .sum())
 }
 
-fn part_b(input: &str) -> usize {
-    let trees = input.lines().map(|s| s.chars().collect_vec()).collect_vec();
+fn part_b(trees: &[Vec<char>]) -> Result<usize, anyhow::Error> {
     let height = trees.len();
-    let width = trees[0].len();
+    let width = trees
+        .first()
+        .map(Vec::len)
+        .ok_or_else(|| anyhow!("No trees found"))?;
 
     let mut max_score = 0;
 
     for y in 0..(height - 1) {
         for x in 0..(width - 1) {
             max_score = max_score.max(
-                score_neg_x(&trees, x, y)
-                    * score_pos_x(&trees, x, y)
-                    * score_neg_y(&trees, x, y)
-                    * score_pos_y(&trees, x, y),
+                score_neg_x(trees, x, y)
+                    * score_pos_x(trees, x, y)
+                    * score_neg_y(trees, x, y)
+                    * score_pos_y(trees, x, y),
             );
         }
     }
 
-    max_score
+    Ok(max_score)
 }
 
 fn score_neg_x(trees: &[Vec<char>], x: usize, y: usize) -> usize {
@@ -133,6 +176,7 @@ fn score_neg_x(trees: &[Vec<char>], x: usize, y: usize) -> usize {
 fn score_pos_x(trees: &[Vec<char>], x: usize, y: usize) -> usize {
     let h = trees[y][x];
 
+    #[allow(clippy::needless_range_loop)]
     for i in (x + 1)..trees[y].len() {
         if trees[y][i] >= h {
             return i - x;
@@ -161,23 +205,16 @@ fn score_pos_y(trees: &[Vec<char>], x: usize, y: usize) -> usize {
             return i - y;
         }
     }
-    trees[x].len() - y - 1
+    trees.len() - y - 1
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example08.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE), 21);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE), 8);
-        Ok(())
-    }
+/// A square forest `size` trees across
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| char::from(rng.range(b'0'..=b'9')))
+                .collect::<String>()
+        })
+        .join("\n")
 }
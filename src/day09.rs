@@ -1,42 +1,67 @@
 use itertools::{repeat_n, Itertools};
 
-const INPUT: &str = include_str!("../resources/input09.txt");
+use crate::{generate::Rng, Answer, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     Right,
     Left,
     Up,
     Down,
 }
 
-pub fn solve_part_a() -> Result<usize, anyhow::Error> {
-    part_a(INPUT)
-}
+const MAX_STEPS: u64 = 10_000_000;
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    const DAY: u32 = 9;
+    const TITLE: &'static str = "Rope Bridge";
+
+    /// Each move of the head, and how many steps it takes
+    type Input<'a> = Vec<(Direction, u32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        let moves = parser::parse(input)?;
+        // Every position the rope visits is remembered, so bound how far it can go
+        if moves.iter().map(|&(_, c)| u64::from(c)).sum::<u64>() > MAX_STEPS {
+            return Err(anyhow::anyhow!(
+                "The head moves more than {MAX_STEPS} steps"
+            ));
+        }
+        Ok(moves)
+    }
 
-pub fn solve_part_b() -> Result<usize, anyhow::Error> {
-    part_b(INPUT)
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input).into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input).into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<usize, anyhow::Error> {
-    let result = parser::parse(input)?
-        .into_iter()
-        .flat_map(|(d, c)| repeat_n(d, c as usize))
+fn part_a(moves: &[(Direction, u32)]) -> usize {
+    moves
+        .iter()
+        .flat_map(|&(d, c)| repeat_n(d, c as usize))
         // head locations
         .scan((0, 0), scan_head)
         // tail locations
         .scan((0, 0), scan_tail)
         // drop duplicates
         .unique()
-        .count();
-
-    Ok(result)
+        .count()
 }
 
-fn part_b(input: &str) -> Result<usize, anyhow::Error> {
-    let result = parser::parse(input)?
-        .into_iter()
-        .flat_map(|(d, c)| repeat_n(d, c as usize))
+fn part_b(moves: &[(Direction, u32)]) -> usize {
+    moves
+        .iter()
+        .flat_map(|&(d, c)| repeat_n(d, c as usize))
         // head locations
         .scan((0, 0), scan_head)
         // tail locations for 9 other knots
@@ -51,19 +76,17 @@ fn part_b(input: &str) -> Result<usize, anyhow::Error> {
         .scan((0, 0), scan_tail)
         // drop duplicates
         .unique()
-        .count();
-
-    Ok(result)
+        .count()
 }
 
 #[allow(clippy::unnecessary_wraps)]
-fn scan_head((x, y): &mut (i32, i32), d: Direction) -> Option<(i32, i32)> {
+const fn scan_head((x, y): &mut (i32, i32), d: Direction) -> Option<(i32, i32)> {
     match d {
         Direction::Right => *x += 1,
         Direction::Left => *x -= 1,
         Direction::Up => *y += 1,
         Direction::Down => *y -= 1,
-    };
+    }
 
     Some((*x, *y))
 }
@@ -77,6 +100,16 @@ fn scan_tail((tx, ty): &mut (i32, i32), (hx, hy): (i32, i32)) -> Option<(i32, i3
     Some((*tx, *ty))
 }
 
+/// `size` moves of the head, each of up to nine steps, so always within `MAX_STEPS`
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| {
+            let direction = rng.choose(&['U', 'D', 'L', 'R']).copied().unwrap_or('U');
+            format!("{direction} {}", rng.range(1..=9))
+        })
+        .join("\n")
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -109,21 +142,3 @@ mod parser {
         ))(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example09.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 13);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 1);
-        Ok(())
-    }
-}
@@ -1,52 +1,77 @@
 use std::vec;
 
-use itertools::process_results;
+use anyhow::anyhow;
+use itertools::{process_results, Itertools};
 use nom::combinator::iterator;
+use tracing::debug;
 
-use crate::finish_parser_it;
-
-const INPUT: &str = include_str!("../resources/input10.txt");
+use crate::{finish_parser_it, generate::Rng, ocr, Answer, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Instruction {
+pub enum Instruction {
     Noop,
     AddX(i32),
 }
 
-pub fn solve_part_a() -> Result<i32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day10;
 
-pub fn solve_part_b() -> Result<String, anyhow::Error> {
-    part_b(INPUT)
-}
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    const TITLE: &'static str = "Cathode-Ray Tube";
 
-fn part_a(input: &str) -> Result<i32, anyhow::Error> {
-    let mut instructions = iterator(input, parser::line);
+    type Input<'a> = Vec<Instruction>;
 
-    let result = process_results(
-        register_values(&mut instructions)
-            .enumerate()
-            .map(|(i, x)| Ok(i32::try_from(1 + i)? * x))
-            .skip(19)
-            .step_by(40),
-        #[allow(clippy::redundant_closure_for_method_calls)]
-        |i| i.sum(),
-    );
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
 
-    finish_parser_it(instructions)?;
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        let image = part_b(input)?;
+        // Not every picture is letters, such as the example's stripes, so those are given as drawn
+        match ocr::read(&image) {
+            Ok(text) => Ok(Answer::Letters { text, image }),
+            Err(e) => {
+                debug!("Couldn't read the letters on the CRT: {e:#}");
+                Ok(Answer::Image(image))
+            }
+        }
+    }
 
-    result
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_b(input: &str) -> Result<String, anyhow::Error> {
-    let mut instructions = iterator(input, parser::line);
+fn parse(input: &str) -> Result<Vec<Instruction>, anyhow::Error> {
+    let mut it = iterator(input, parser::line);
+    let instructions = it.collect();
+    finish_parser_it(input, it, parser::line)?;
+    Ok(instructions)
+}
+
+fn part_a(instructions: &[Instruction]) -> Result<i64, anyhow::Error> {
+    register_values(instructions.iter().copied())
+        .enumerate()
+        .skip(19)
+        .step_by(40)
+        .try_fold(0i64, |total, (i, x)| {
+            i64::try_from(1 + i)?
+                .checked_mul(x)
+                .and_then(|strength| total.checked_add(strength))
+                .ok_or_else(|| anyhow!("Signal strength is too large"))
+        })
+}
 
-    let result = process_results(
-        register_values(&mut instructions)
+fn part_b(instructions: &[Instruction]) -> Result<String, anyhow::Error> {
+    process_results(
+        register_values(instructions.iter().copied())
             .enumerate()
             .map(|(i, x)| {
-                let n = i32::try_from(i % 40)?;
+                let n = i64::try_from(i % 40)?;
 
                 Ok(if n != 39 {
                     if (n - 1..=n + 1).contains(&x) {
@@ -62,14 +87,12 @@ fn part_b(input: &str) -> Result<String, anyhow::Error> {
             }),
         #[allow(clippy::redundant_closure_for_method_calls)]
         |i| i.collect(),
-    );
-
-    finish_parser_it(instructions)?;
-
-    result
+    )
 }
 
-fn register_values(input: impl Iterator<Item = Instruction>) -> impl Iterator<Item = i32> {
+/// The value of the X register during each cycle, wide enough that no input of a sensible size
+/// can overflow it
+fn register_values(input: impl Iterator<Item = Instruction>) -> impl Iterator<Item = i64> {
     input
         .flat_map(|i| match i {
             Instruction::Noop => vec![i],
@@ -80,13 +103,29 @@ fn register_values(input: impl Iterator<Item = Instruction>) -> impl Iterator<It
             match i {
                 Instruction::Noop => {}
                 Instruction::AddX(a) => {
-                    *x += a;
+                    *x += i64::from(a);
                 }
-            };
+            }
             Some(current)
         })
 }
 
+/// A program of `size` instructions that keeps the sprite on the screen
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let mut x = 1;
+    (0..size)
+        .map(|_| {
+            if rng.one_in(3) {
+                "noop".to_owned()
+            } else {
+                let v = rng.range((-x).max(-5)..=(39 - x).min(5));
+                x += v;
+                format!("addx {v}")
+            }
+        })
+        .join("\n")
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -117,16 +156,11 @@ mod tests {
     const EXAMPLE: &str = include_str!("../resources/example10.txt");
 
     #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 13140);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
+    fn example_b_draws_the_picture() -> Result<(), anyhow::Error> {
         assert_eq!(
-            part_b(EXAMPLE).unwrap(),
-            "##..##..##..##..##..##..##..##..##..##..
+            part_b(&parse(EXAMPLE)?)?,
+            "\
+##..##..##..##..##..##..##..##..##..##..
 ###...###...###...###...###...###...###.
 ####....####....####....####....####....
 #####.....#####.....#####.....#####.....
@@ -136,4 +170,11 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn example_b_is_not_letters() -> Result<(), anyhow::Error> {
+        let image = part_b(&parse(EXAMPLE)?)?;
+        assert_eq!(Day10::part_b(&parse(EXAMPLE)?)?, Answer::Image(image));
+        Ok(())
+    }
 }
@@ -1,18 +1,18 @@
-use std::{cmp::Reverse, num::TryFromIntError};
+use std::{cmp::Reverse, num::TryFromIntError, ops::RangeInclusive};
 
+use anyhow::anyhow;
 use itertools::Itertools;
+use tracing::{debug, trace};
 
-use crate::final_parser;
+use crate::{final_parser, generate::Rng, Answer, Solution};
 
-const INPUT: &str = include_str!("../resources/input11.txt");
-
-#[derive(Debug, PartialEq, Eq)]
-struct Monkey {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
     id: u32,
-    pub items: Vec<u32>,
-    pub operation: Operation,
-    pub test: Test,
-    pub inspection_count: u64,
+    items: Vec<u32>,
+    operation: Operation,
+    test: Test,
+    inspection_count: u64,
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Operation {
@@ -22,7 +22,9 @@ enum Operation {
 impl Operation {
     fn apply(&self, modulus: u32, old: u32) -> Result<u32, TryFromIntError> {
         match self {
-            Self::Add(a, b) => Ok((a.value(old) + b.value(old)) % modulus),
+            Self::Add(a, b) => ((u64::from(a.value(old)) + u64::from(b.value(old)))
+                % u64::from(modulus))
+            .try_into(),
             Self::Multiply(a, b) => ((u64::from(a.value(old)) * u64::from(b.value(old)))
                 % u64::from(modulus))
             .try_into(),
@@ -51,7 +53,7 @@ struct Test {
 }
 impl Test {
     const fn apply(&self, i: u32) -> u32 {
-        if i % self.divisor == 0 {
+        if i.is_multiple_of(self.divisor) {
             self.pass
         } else {
             self.fail
@@ -59,28 +61,73 @@ impl Test {
     }
 }
 
-pub fn solve_part_a() -> Result<u64, anyhow::Error> {
-    part_a(INPUT)
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    const TITLE: &'static str = "Monkey in the Middle";
+    const GENERATE_SIZES: RangeInclusive<usize> = 2..=10_000;
+
+    type Input<'a> = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        generate(size, rng)
+    }
 }
 
-pub fn solve_part_b() -> Result<u64, anyhow::Error> {
-    part_b(INPUT)
+fn parse(input: &str) -> Result<Vec<Monkey>, anyhow::Error> {
+    let monkeys = final_parser(parser::parse)(input)?;
+
+    for m in &monkeys {
+        if m.test.divisor == 0 {
+            return Err(anyhow!("Monkey {} tests for divisibility by 0", m.id));
+        }
+        if let Some(to) = [m.test.pass, m.test.fail]
+            .into_iter()
+            .find(|&to| to as usize >= monkeys.len())
+        {
+            return Err(anyhow!("Monkey {} throws to missing monkey {to}", m.id));
+        }
+    }
+    Ok(monkeys)
 }
 
-fn part_a(input: &str) -> Result<u64, anyhow::Error> {
-    most_active(input, true, 20)
+fn part_a(monkeys: &[Monkey]) -> Result<u64, anyhow::Error> {
+    most_active(monkeys, true, 20)
 }
 
-fn part_b(input: &str) -> Result<u64, anyhow::Error> {
-    most_active(input, false, 10000)
+fn part_b(monkeys: &[Monkey]) -> Result<u64, anyhow::Error> {
+    most_active(monkeys, false, 10000)
 }
 
-fn most_active(input: &str, reduce_worry: bool, rounds: u32) -> Result<u64, anyhow::Error> {
-    let mut monkeys = final_parser(parser::parse)(input)?;
-    let common_mod: u32 = monkeys.iter().map(|m| m.test.divisor).unique().product();
-    for _ in 0..rounds {
+fn most_active(monkeys: &[Monkey], reduce_worry: bool, rounds: u32) -> Result<u64, anyhow::Error> {
+    let mut monkeys = monkeys.to_vec();
+    let common_mod = monkeys
+        .iter()
+        .map(|m| m.test.divisor)
+        .unique()
+        .try_fold(1u32, u32::checked_mul)
+        .ok_or_else(|| anyhow!("The monkeys' divisors are too large to combine"))?;
+    for r in 1..=rounds {
         round(reduce_worry, common_mod, &mut monkeys)?;
+        trace!(round = r, "monkeys finished a round");
     }
+    debug!(
+        inspections = ?monkeys.iter().map(|m| m.inspection_count).collect_vec(),
+        "monkeys finished every round"
+    );
     Ok(monkeys
         .into_iter()
         .map(|m| m.inspection_count)
@@ -110,6 +157,45 @@ fn round(reduce_worry: bool, common_mod: u32, monkeys: &mut [Monkey]) -> Result<
     Ok(())
 }
 
+/// `size` monkeys throwing items to each other, testing for small primes so that their divisors
+/// can always be combined
+fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+    if size < 2 {
+        return Err(anyhow!("A monkey needs another monkey to throw to"));
+    }
+
+    Ok((0..size)
+        .map(|id| {
+            let items = (0..rng.range(1..=4))
+                .map(|_| rng.range(50..=99_u32))
+                .join(", ");
+            let operation = match rng.range(0..=2) {
+                0 => "old * old".to_owned(),
+                1 => format!("old * {}", rng.range(2..=19)),
+                _ => format!("old + {}", rng.range(1..=8)),
+            };
+            let divisor = rng
+                .choose(&[2, 3, 5, 7, 11, 13, 17, 19, 23])
+                .copied()
+                .unwrap_or(2);
+            let mut other = || {
+                let m = rng.range(0..=size - 2);
+                m + usize::from(m >= id)
+            };
+            let (pass, fail) = (other(), other());
+
+            format!(
+                "Monkey {id}:\n  \
+                 Starting items: {items}\n  \
+                 Operation: new = {operation}\n  \
+                 Test: divisible by {divisor}\n    \
+                 If true: throw to monkey {pass}\n    \
+                 If false: throw to monkey {fail}"
+            )
+        })
+        .join("\n\n"))
+}
+
 mod parser {
 
     use nom::{
@@ -183,21 +269,3 @@ mod parser {
         )(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example11.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 10605);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 2713310158);
-        Ok(())
-    }
-}
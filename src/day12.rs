@@ -1,68 +1,114 @@
-use std::collections::BinaryHeap;
+use std::{collections::BinaryHeap, ops::RangeInclusive};
 
 use anyhow::anyhow;
 use itertools::Itertools;
 
-const INPUT: &str = include_str!("../resources/input12.txt");
+use crate::{
+    cancel::{self, Token},
+    generate::Rng,
+    svg, Alternative, Answer, Part, Solution,
+};
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day12;
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
-}
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    const TITLE: &'static str = "Hill Climbing Algorithm";
+    // Rising from a to z takes 26 columns, and the map grows with the size squared
+    const GENERATE_SIZES: RangeInclusive<usize> = 26..=5_000;
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let (start_x, start_y, _) = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, r)| r.chars().enumerate().map(move |(x, c)| (x, y, c)))
-        .find(|(_, _, c)| *c == 'S')
-        .ok_or_else(|| anyhow::anyhow!("No start marker found"))?;
+    type Input<'a> = Heightmap;
 
-    let (end_x, end_y, _) = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, r)| r.chars().enumerate().map(move |(x, c)| (x, y, c)))
-        .find(|(_, _, c)| *c == 'E')
-        .ok_or_else(|| anyhow::anyhow!("No end marker found"))?;
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
 
-    //replace S and E with a and z
-    let input = input
-        .replace('S', "a")
-        .replace('E', "z")
-        .lines()
-        .map(|l| l.chars().map(u32::from).collect_vec())
-        .collect_vec();
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    const ALTERNATIVES: &'static [Alternative<Self>] = &[
+        Alternative {
+            name: "bfs",
+            part: Part::A,
+            solve: |input| Ok(part_a_bfs(input)?.into()),
+        },
+        Alternative {
+            name: "a_star",
+            part: Part::B,
+            solve: |input| Ok(part_b_a_star(input)?.into()),
+        },
+    ];
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        generate(size, rng)
+    }
+
+    fn visualise(input: &Self::Input<'_>) -> Option<String> {
+        Some(visualise(input))
+    }
+}
 
-    a_star(
-        &input,
-        (u32::try_from(end_x)?, u32::try_from(end_y)?),
-        (u32::try_from(start_x)?, u32::try_from(start_y)?),
-    )
+/// The height of every square, with the start and end markers replaced by their heights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heightmap {
+    heights: Vec<Vec<u32>>,
+    start: (u32, u32),
+    end: (u32, u32),
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let (end_x, end_y, _) = input
-        .lines()
-        .enumerate()
-        .flat_map(|(y, r)| r.chars().enumerate().map(move |(x, c)| (x, y, c)))
-        .find(|(_, _, c)| *c == 'E')
-        .ok_or_else(|| anyhow::anyhow!("No end marker found"))?;
+fn parse(input: &str) -> Result<Heightmap, anyhow::Error> {
+    let find = |marker| -> Result<(u32, u32), anyhow::Error> {
+        let (x, y, _) = input
+            .lines()
+            .enumerate()
+            .flat_map(|(y, r)| r.chars().enumerate().map(move |(x, c)| (x, y, c)))
+            .find(|(_, _, c)| *c == marker)
+            .ok_or_else(|| anyhow!("No {marker} marker found"))?;
+        Ok((u32::try_from(x)?, u32::try_from(y)?))
+    };
+    let start = find('S')?;
+    let end = find('E')?;
 
     //replace S and E with a and z
-    let input = input
+    let heights = input
         .replace('S', "a")
         .replace('E', "z")
         .lines()
         .map(|l| l.chars().map(u32::from).collect_vec())
         .collect_vec();
 
-    let steps = bfs(&input, (u32::try_from(end_x)?, u32::try_from(end_y)?));
+    if !heights.iter().map(Vec::len).all_equal() {
+        return Err(anyhow!("Every row should be the same length"));
+    }
 
-    // process_results(
-    input
+    if heights
+        .iter()
+        .flatten()
+        .all(|&h| ('a' as u32..='z' as u32).contains(&h))
+    {
+        Ok(Heightmap {
+            heights,
+            start,
+            end,
+        })
+    } else {
+        Err(anyhow!("Heights should be lowercase letters"))
+    }
+}
+
+fn part_a(map: &Heightmap) -> Result<u32, anyhow::Error> {
+    a_star(&map.heights, map.end, map.start)
+}
+
+fn part_b(map: &Heightmap) -> Result<u32, anyhow::Error> {
+    let steps = bfs(&map.heights, map.end)?;
+
+    map.heights
         .iter()
         .enumerate()
         .flat_map(|(y, r)| r.iter().enumerate().map(move |(x, c)| (x, y, *c)))
@@ -72,6 +118,30 @@ fn part_b(input: &str) -> Result<u32, anyhow::Error> {
         .ok_or_else(|| anyhow!("No routes found"))
 }
 
+/// Part a from the steps to every square, rather than searching for the start
+fn part_a_bfs(map: &Heightmap) -> Result<u32, anyhow::Error> {
+    let (x, y) = map.start;
+    bfs(&map.heights, map.end)?[y as usize][x as usize].ok_or_else(|| anyhow!("No routes found"))
+}
+
+/// Part b by searching for each of the lowest squares in turn, rather than finding the steps to
+/// every square at once
+fn part_b_a_star(map: &Heightmap) -> Result<u32, anyhow::Error> {
+    let mut shortest = None;
+    for (y, r) in map.heights.iter().enumerate() {
+        for (x, _) in r.iter().enumerate().filter(|(_, &c)| c == 'a' as u32) {
+            let square = (u32::try_from(x)?, u32::try_from(y)?);
+            // Squares with no route are left out, but running out of time ends the search
+            match a_star(&map.heights, map.end, square) {
+                Ok(steps) => shortest = Some(shortest.map_or(steps, |s: u32| s.min(steps))),
+                Err(e) if cancel::timed_out(&e) => return Err(e),
+                Err(_) => {}
+            }
+        }
+    }
+    shortest.ok_or_else(|| anyhow!("No routes found"))
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct Location {
     pub coordinates: (u32, u32),
@@ -83,7 +153,7 @@ struct Location {
 
 impl PartialOrd for Location {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (other.steps + other.distance).partial_cmp(&(self.steps + self.distance))
+        Some(self.cmp(other))
     }
 }
 
@@ -113,8 +183,14 @@ fn a_star(input: &[Vec<u32>], start: (u32, u32), end: (u32, u32)) -> Result<u32,
 
     cheapest[start_y as usize][start_x as usize] = Some(0);
 
+    let token = Token::current();
+    let mut searched = 0_u32;
     while candidate.coordinates != end {
-        // for _ in 0..10 {
+        // Checking the time costs more than a step, so only check every so often
+        searched = searched.wrapping_add(1);
+        if searched.is_multiple_of(1024) {
+            token.check()?;
+        }
         let (x, y) = candidate.coordinates;
 
         //up
@@ -153,7 +229,7 @@ fn a_star(input: &[Vec<u32>], start: (u32, u32), end: (u32, u32)) -> Result<u32,
     Ok(candidate.steps)
 }
 
-fn bfs(input: &[Vec<u32>], start: (u32, u32)) -> Vec<Vec<Option<u32>>> {
+fn bfs(input: &[Vec<u32>], start: (u32, u32)) -> Result<Vec<Vec<Option<u32>>>, anyhow::Error> {
     let mut candidates = BinaryHeap::new();
 
     let mut candidate = Location {
@@ -173,8 +249,13 @@ fn bfs(input: &[Vec<u32>], start: (u32, u32)) -> Vec<Vec<Option<u32>>> {
 
     cheapest[start_y as usize][start_x as usize] = Some(0);
 
+    let token = Token::current();
+    let mut searched = 0_u32;
     loop {
-        // for _ in 0..10 {
+        searched = searched.wrapping_add(1);
+        if searched.is_multiple_of(1024) {
+            token.check()?;
+        }
         let (x, y) = candidate.coordinates;
 
         //up
@@ -208,7 +289,7 @@ fn bfs(input: &[Vec<u32>], start: (u32, u32)) -> Vec<Vec<Option<u32>>> {
         if let Some(c) = candidates.pop() {
             candidate = c;
         } else {
-            return cheapest;
+            return Ok(cheapest);
         }
     }
 }
@@ -255,20 +336,58 @@ const fn distance((start_x, start_y): (u32, u32), (end_x, end_y): (u32, u32)) ->
     start_x.abs_diff(end_x) + start_y.abs_diff(end_y)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example12.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 31);
-        Ok(())
-    }
+/// The heightmap shaded from dark green in the valleys to white on the peaks, with the start in
+/// red and the end in blue
+fn visualise(map: &Heightmap) -> String {
+    let width = map.heights.first().map_or(0, Vec::len);
+    svg::pixels(width, map.heights.len(), |x, y| {
+        let square = (u32::try_from(x).ok()?, u32::try_from(y).ok()?);
+        Some(if square == map.start {
+            "#d03030".to_owned()
+        } else if square == map.end {
+            "#3050d0".to_owned()
+        } else {
+            let level = map.heights[y][x] - 'a' as u32;
+            format!(
+                "rgb({},{},{})",
+                40 + level * 8,
+                80 + level * 6,
+                40 + level * 8
+            )
+        })
+    })
+}
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 29);
-        Ok(())
+/// A heightmap `size` squares across and down, rising from a in the west to z in the east, with
+/// a clear route along the start's row
+fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+    if size < 26 {
+        return Err(anyhow!(
+            "A heightmap needs to be at least 26 squares across to climb from a to z"
+        ));
     }
+    let start = rng.range(0..=size - 1);
+    let end = rng.range(0..=size - 1);
+
+    Ok((0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| {
+                    let height = u8::try_from(x * 25 / (size - 1)).unwrap_or(25);
+                    let dip = if y == start || x == size - 1 {
+                        0
+                    } else {
+                        rng.range(0..=2)
+                    };
+                    if (x, y) == (0, start) {
+                        'S'
+                    } else if (x, y) == (size - 1, end) {
+                        'E'
+                    } else {
+                        char::from(b'a' + height.saturating_sub(dip))
+                    }
+                })
+                .collect::<String>()
+        })
+        .join("\n"))
 }
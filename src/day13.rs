@@ -3,23 +3,63 @@ use std::{cmp::Ordering, fmt::Debug};
 use itertools::Itertools;
 use nom::combinator::iterator;
 
-use crate::finish_parser_it;
+use crate::{finish_parser_it, generate::Rng, Answer, ParseError, Solution};
 
-const INPUT: &str = include_str!("../resources/input13.txt");
+pub struct Day13;
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Distress Signal";
+
+    /// Each pair of packets
+    type Input<'a> = Vec<(Packet, Packet)>;
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
+pub type Packet = Vec<PacketItem>;
+
+/// Packets nested deeper than this would overflow the stack while being parsed or compared
+const MAX_DEPTH: usize = 256;
+
+fn parse(input: &str) -> Result<Vec<(Packet, Packet)>, anyhow::Error> {
+    let mut depth = 0usize;
+    for (i, c) in input.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            '\n' => depth = 0,
+            _ => {}
+        }
+        if depth > MAX_DEPTH {
+            return Err(ParseError::new(input, &input[i..], "packet is nested too deeply").into());
+        }
+    }
+
     let mut it = iterator(input, parser::parse_pairs);
+    let pairs = it.collect();
+    finish_parser_it(input, it, parser::parse_pairs)?;
+    Ok(pairs)
+}
 
-    let result = it
-        .map(|(a, b)| a.cmp(&b))
+fn part_a(pairs: &[(Packet, Packet)]) -> Result<u64, anyhow::Error> {
+    let result = pairs
+        .iter()
+        .map(|(a, b)| a.cmp(b))
         .enumerate()
         .filter_map(|(a, b)| {
             if b == Ordering::Less {
@@ -30,29 +70,27 @@ fn part_a(input: &str) -> Result<u32, anyhow::Error> {
         })
         .sum::<usize>();
 
-    finish_parser_it(it)?;
     Ok(result.try_into()?)
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let mut it = iterator(input, parser::parse_individual);
-
+fn part_b(pairs: &[(Packet, Packet)]) -> Result<u64, anyhow::Error> {
     let div1 = vec![PacketItem::List(vec![PacketItem::Integer(2)])];
     let div2 = vec![PacketItem::List(vec![PacketItem::Integer(6)])];
 
-    let sorted = it
-        .chain([div1.clone(), div2.clone()].into_iter())
+    let sorted = pairs
+        .iter()
+        .flat_map(|(a, b)| [a, b])
+        .chain([&div1, &div2])
         .sorted_unstable()
         .collect_vec();
 
-    finish_parser_it(it)?;
-    let result = (sorted.binary_search(&div1).unwrap_or(0) + 1)
-        * (sorted.binary_search(&div2).unwrap_or(0) + 1);
+    let result = (sorted.binary_search(&&div1).unwrap_or(0) + 1)
+        * (sorted.binary_search(&&div2).unwrap_or(0) + 1);
     Ok(result.try_into()?)
 }
 
 #[derive(PartialEq, Eq, Clone)]
-enum PacketItem {
+pub enum PacketItem {
     List(Vec<Self>),
     Integer(u32),
 }
@@ -83,6 +121,26 @@ impl Debug for PacketItem {
     }
 }
 
+/// `size` pairs of packets, nested up to four lists deep
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| format!("{}\n{}", random_packet(rng, 0), random_packet(rng, 0)))
+        .join("\n\n")
+}
+
+fn random_packet(rng: &mut Rng, depth: usize) -> String {
+    let items = (0..rng.range(0..=4))
+        .map(|_| {
+            if depth < 4 && rng.one_in(3) {
+                random_packet(rng, depth + 1)
+            } else {
+                rng.range(0..=10_u32).to_string()
+            }
+        })
+        .join(",");
+    format!("[{items}]")
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -103,17 +161,6 @@ mod parser {
         )(input)
     }
 
-    pub(super) fn parse_individual(input: &str) -> IResult<&str, Vec<PacketItem>> {
-        terminated(
-            packet,
-            alt((
-                recognize(pair(line_ending, line_ending)),
-                recognize(line_ending),
-                recognize(eof),
-            )),
-        )(input)
-    }
-
     fn packet(input: &str) -> IResult<&str, Vec<PacketItem>> {
         delimited(
             tag("["),
@@ -125,21 +172,3 @@ mod parser {
         )(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example13.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 13);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 140);
-        Ok(())
-    }
-}
@@ -1,29 +1,68 @@
 use itertools::Itertools;
 use nom::combinator::iterator;
 
-use crate::finish_parser_it;
+use crate::{finish_parser_it, generate::Rng, svg, Answer, Solution};
 
-const INPUT: &str = include_str!("../resources/input14.txt");
+pub struct Day14;
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Regolith Reservoir";
+
+    /// The corners of each path of rock
+    type Input<'a> = Vec<Vec<(u32, u32)>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
+
+    fn visualise(input: &Self::Input<'_>) -> Option<String> {
+        visualise(input).ok()
+    }
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let mut lines = iterator(input, parser::parse);
+fn parse(input: &str) -> Result<Vec<Vec<(u32, u32)>>, anyhow::Error> {
+    let mut it = iterator(input, parser::parse);
+    let paths: Vec<Vec<(u32, u32)>> = it.collect();
+    finish_parser_it(input, it, parser::parse)?;
+
+    if let Some((x, y)) = paths
+        .iter()
+        .flatten()
+        .find(|&&(x, y)| x >= 1000 || y >= 997)
+    {
+        return Err(anyhow::anyhow!("Rock at {x},{y} is outside the cave"));
+    }
+    Ok(paths)
+}
 
+fn draw_cave(paths: &[Vec<(u32, u32)>]) -> Result<Vec<[Tile; 1000]>, anyhow::Error> {
     let mut cave = vec![[Tile::Empty; 1000]; 1000];
 
-    for l in &mut lines {
-        for (a, b) in l.into_iter().tuple_windows() {
+    for path in paths {
+        for (&a, &b) in path.iter().tuple_windows() {
             draw_line(&mut cave, a, b)?;
         }
     }
 
+    Ok(cave)
+}
+
+fn part_a(paths: &[Vec<(u32, u32)>]) -> Result<u32, anyhow::Error> {
+    let mut cave = draw_cave(paths)?;
+
     let max_depth = max_depth(&cave)
         .ok_or_else(|| anyhow::anyhow!("No rocks found"))?
         .try_into()?;
@@ -31,7 +70,7 @@ fn part_a(input: &str) -> Result<u32, anyhow::Error> {
     loop {
         let (mut sand_x, mut sand_y) = (500, 0);
         loop {
-            if let Some(next) = get_next_empty_tile(&cave, (sand_x, sand_y)) {
+            if let Some(next) = get_next_empty_tile(&cave, (sand_x, sand_y))? {
                 (sand_x, sand_y) = next;
             } else {
                 cave[sand_y as usize][sand_x as usize] = Tile::Sand;
@@ -42,12 +81,11 @@ fn part_a(input: &str) -> Result<u32, anyhow::Error> {
                 break;
             }
         }
-        if sand_y > max_depth {
+        if sand_y > max_depth || (sand_x, sand_y) == (500, 0) {
             break;
         }
     }
 
-    finish_parser_it(lines)?;
     Ok(cave
         .into_iter()
         .map(|r| r.iter().filter(|&&t| t == Tile::Sand).count())
@@ -55,16 +93,17 @@ fn part_a(input: &str) -> Result<u32, anyhow::Error> {
         .try_into()?)
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let mut lines = iterator(input, parser::parse);
-
-    let mut cave = vec![[Tile::Empty; 1000]; 1000];
+fn part_b(paths: &[Vec<(u32, u32)>]) -> Result<u32, anyhow::Error> {
+    Ok(fill_to_source(paths)?
+        .into_iter()
+        .map(|r| r.iter().filter(|&&t| t == Tile::Sand).count())
+        .sum::<usize>()
+        .try_into()?)
+}
 
-    for l in &mut lines {
-        for (a, b) in l.into_iter().tuple_windows() {
-            draw_line(&mut cave, a, b)?;
-        }
-    }
+/// The cave with its floor drawn in, once sand has piled up to the source
+fn fill_to_source(paths: &[Vec<(u32, u32)>]) -> Result<Vec<[Tile; 1000]>, anyhow::Error> {
+    let mut cave = draw_cave(paths)?;
 
     let max_d: u32 = max_depth(&cave)
         .ok_or_else(|| anyhow::anyhow!("No rocks found"))?
@@ -79,7 +118,7 @@ fn part_b(input: &str) -> Result<u32, anyhow::Error> {
     loop {
         let (mut sand_x, mut sand_y) = (500, 0);
         loop {
-            if let Some(next) = get_next_empty_tile(&cave, (sand_x, sand_y)) {
+            if let Some(next) = get_next_empty_tile(&cave, (sand_x, sand_y))? {
                 (sand_x, sand_y) = next;
             } else {
                 cave[sand_y as usize][sand_x as usize] = Tile::Sand;
@@ -99,32 +138,59 @@ fn part_b(input: &str) -> Result<u32, anyhow::Error> {
         }
     }
 
-    finish_parser_it(lines)?;
-    Ok(cave
-        .into_iter()
-        .map(|r| r.iter().filter(|&&t| t == Tile::Sand).count())
-        .sum::<usize>()
-        .try_into()?)
+    Ok(cave)
+}
+
+/// The rock and the sand piled up to the source, cropped to the pile and drawn down to the floor
+fn visualise(paths: &[Vec<(u32, u32)>]) -> Result<String, anyhow::Error> {
+    let cave = fill_to_source(paths)?;
+    let depth = max_depth(&cave).ok_or_else(|| anyhow::anyhow!("No rocks found"))?;
+    let (left, right) = cave[..depth]
+        .iter()
+        .flat_map(|r| r.iter().positions(|&t| t != Tile::Empty))
+        .minmax()
+        .into_option()
+        .ok_or_else(|| anyhow::anyhow!("Nothing to draw"))?;
+
+    Ok(svg::pixels(
+        right - left + 1,
+        depth + 1,
+        |x, y| match cave[y][left + x] {
+            Tile::Empty => None,
+            Tile::Rock => Some("#606060".to_owned()),
+            Tile::Sand => Some("#e0c080".to_owned()),
+        },
+    ))
 }
 
 fn max_depth(cave: &[[Tile; 1000]]) -> Option<usize> {
     cave.iter()
         .enumerate()
-        .filter_map(|(i, &l)| l.iter().any(|&t| t == Tile::Rock).then_some(i))
+        .filter_map(|(i, &l)| l.contains(&Tile::Rock).then_some(i))
         .max()
 }
 
-fn get_next_empty_tile(cave: &[[Tile; 1000]], (sand_x, sand_y): (u32, u32)) -> Option<(u32, u32)> {
-    if cave[(sand_y + 1) as usize][sand_x as usize] == Tile::Empty {
-        Some((sand_x, sand_y + 1))
-    } else if cave[(sand_y + 1) as usize][(sand_x - 1) as usize] == Tile::Empty {
-        Some((sand_x - 1, sand_y + 1))
-    } else if cave[(sand_y + 1) as usize][(sand_x + 1) as usize] == Tile::Empty {
-        Some((sand_x + 1, sand_y + 1))
-    } else {
-        None
+fn get_next_empty_tile(
+    cave: &[[Tile; 1000]],
+    (sand_x, sand_y): (u32, u32),
+) -> Result<Option<(u32, u32)>, anyhow::Error> {
+    if sand_x == 0 || sand_x as usize == cave[0].len() - 1 || sand_y as usize + 1 >= cave.len() {
+        return Err(anyhow::anyhow!("Sand escape!"));
     }
+
+    Ok(
+        if cave[(sand_y + 1) as usize][sand_x as usize] == Tile::Empty {
+            Some((sand_x, sand_y + 1))
+        } else if cave[(sand_y + 1) as usize][(sand_x - 1) as usize] == Tile::Empty {
+            Some((sand_x - 1, sand_y + 1))
+        } else if cave[(sand_y + 1) as usize][(sand_x + 1) as usize] == Tile::Empty {
+            Some((sand_x + 1, sand_y + 1))
+        } else {
+            None
+        },
+    )
 }
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
@@ -152,6 +218,39 @@ fn draw_line(
     Ok(())
 }
 
+/// `size` paths of rock, reaching deeper into the cave the more there are while leaving room for
+/// the sand to pile up to the source
+fn generate(size: usize, rng: &mut Rng) -> String {
+    let depth = u32::try_from(size).map_or(480, |s| s.saturating_add(10).min(480));
+    let (xs, ys) = ((500 - depth, 500 + depth), (2, depth));
+
+    (0..size)
+        .map(|_| {
+            let mut corner = (rng.range(xs.0..=xs.1), rng.range(ys.0..=ys.1));
+            let mut path = vec![corner];
+            let horizontal = rng.one_in(2);
+            for i in 0..rng.range(1..=4) {
+                let delta = rng.range(1..=8);
+                let back = rng.one_in(2);
+                let step = |v: u32, (low, high): (u32, u32)| {
+                    if back {
+                        v.saturating_sub(delta).max(low)
+                    } else {
+                        (v + delta).min(high)
+                    }
+                };
+                corner = if (i % 2 == 0) == horizontal {
+                    (step(corner.0, xs), corner.1)
+                } else {
+                    (corner.0, step(corner.1, ys))
+                };
+                path.push(corner);
+            }
+            path.iter().map(|(x, y)| format!("{x},{y}")).join(" -> ")
+        })
+        .join("\n")
+}
+
 mod parser {
     use nom::{
         branch::alt,
@@ -170,21 +269,3 @@ mod parser {
         )(input)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example14.txt");
-
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 24);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 93);
-        Ok(())
-    }
-}
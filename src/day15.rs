@@ -1,38 +1,64 @@
+use std::ops::RangeInclusive;
+
 use itertools::Itertools;
 use nom::combinator::iterator;
 use rayon::prelude::*;
+use tracing::debug;
 
-use crate::finish_parser_it;
+use crate::{
+    cancel::{TimedOut, Token},
+    finish_parser_it,
+    generate::Rng,
+    Answer, Solution,
+};
 
-const INPUT: &str = include_str!("../resources/input15.txt");
+pub struct Day15;
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT, 2_000_000)
-}
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Beacon Exclusion Zone";
+    // Part b searches the same area whatever the size, so slows down quickly with more sensors
+    const GENERATE_SIZES: RangeInclusive<usize> = 4..=32;
+
+    type Input<'a> = Vec<Sensor>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
 
-pub fn solve_part_b() -> Result<u64, anyhow::Error> {
-    part_b(INPUT, 4_000_000)
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input, 2_000_000)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input, 4_000_000)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        generate(size, rng)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
-struct Sensor {
+pub struct Sensor {
     location: (i32, i32),
     beacon: (i32, i32),
     distance: i32,
 }
 
 impl Sensor {
-    pub fn new(location: (i32, i32), beacon: (i32, i32)) -> Self {
+    fn new(location: (i32, i32), beacon: (i32, i32)) -> Self {
         Self {
             location,
             beacon,
-            distance: (location.0.abs_diff(beacon.0) + location.1.abs_diff(beacon.1))
+            distance: (location.0.abs_diff(beacon.0))
+                .saturating_add(location.1.abs_diff(beacon.1))
                 .try_into()
                 .unwrap_or(i32::MAX),
         }
     }
 
-    pub fn range_at(&self, y: i32) -> Option<(i32, i32)> {
+    fn range_at(&self, y: i32) -> Option<(i32, i32)> {
         let h: i32 = self.location.1.abs_diff(y).try_into().unwrap_or(i32::MAX);
         if h > self.distance {
             None
@@ -43,37 +69,34 @@ impl Sensor {
     }
 }
 
-fn part_a(input: &str, target_y: i32) -> Result<u32, anyhow::Error> {
-    let mut it = iterator(input, parser::parse);
+/// Keeps every distance and range well within an `i32`
+const MAX_COORDINATE: i32 = 100_000_000;
 
-    let sensors = &it.map(|(s, b)| Sensor::new(s, b)).collect_vec();
+fn parse(input: &str) -> Result<Vec<Sensor>, anyhow::Error> {
+    let mut it = iterator(input, parser::parse);
+    let sensors: Vec<Sensor> = it.map(|(s, b)| Sensor::new(s, b)).collect();
+    finish_parser_it(input, it, parser::parse)?;
 
-    let ranges = sensors
+    if sensors.is_empty() {
+        return Err(anyhow::anyhow!("No sensors found"));
+    }
+    if let Some((x, y)) = sensors
         .iter()
-        .filter_map(|s| s.range_at(target_y))
-        .flat_map(|(s, e)| [(s, 1), (e, -1)])
-        .sorted_unstable()
-        .group_by(|&(i, _)| i)
-        .into_iter()
-        .map(|(i, g)| (i, g.map(|(_, c)| c).sum::<i32>()))
-        .filter(|(_, s)| *s != 0)
-        .collect_vec();
-
-    let mut num = 0;
-    let mut start = 0;
-    let mut covered = 0;
-    for (x, change) in ranges {
-        if covered == 0 {
-            assert!(change > 0);
-            start = x;
-        }
-        covered += change;
-        if covered == 0 {
-            assert!(change < 0);
-            num += x - start + 1;
-            start = 0;
-        }
+        .flat_map(|s| [s.location, s.beacon])
+        .find(|&(x, y)| x.unsigned_abs().max(y.unsigned_abs()) > MAX_COORDINATE.unsigned_abs())
+    {
+        return Err(anyhow::anyhow!(
+            "x={x}, y={y} is further than {MAX_COORDINATE} from the origin"
+        ));
     }
+    Ok(sensors)
+}
+
+fn part_a(sensors: &[Sensor], target_y: i32) -> Result<u32, anyhow::Error> {
+    let num: i32 = covered_ranges(sensors, target_y)
+        .into_iter()
+        .map(|(start, end)| end - start)
+        .sum();
 
     let becons = sensors
         .iter()
@@ -81,70 +104,111 @@ fn part_a(input: &str, target_y: i32) -> Result<u32, anyhow::Error> {
         .unique()
         .count();
 
-    finish_parser_it(it)?;
     Ok((num - i32::try_from(becons)?).try_into()?)
 }
 
-fn part_b(input: &str, max_val: i32) -> Result<u64, anyhow::Error> {
-    let mut it = iterator(input, parser::parse);
-
-    let sensors = &it.map(|(s, b)| Sensor::new(s, b)).collect_vec();
-
-    finish_parser_it(it)?;
-
-    let result = (0..max_val)
+fn part_b(sensors: &[Sensor], max_val: i32) -> Result<u64, anyhow::Error> {
+    let token = Token::current();
+    let result = (0..=max_val)
         .into_par_iter()
-        .filter_map(|target_y| find_beacon(sensors, target_y, max_val))
-        .find_first(|_| true);
+        .map(|target_y| {
+            // Checking the time costs more than a row, so only check every so often
+            if target_y % 1024 == 0 {
+                token.check()?;
+            }
+            Ok(find_beacon(sensors, target_y, max_val))
+        })
+        .find_first(|row: &Result<_, TimedOut>| !matches!(row, Ok(None)));
 
-    let (x, y) = result.ok_or_else(|| anyhow::anyhow!("Not beacon found"))?;
+    let (x, y) = result
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| anyhow::anyhow!("Not beacon found"))?;
+    debug!(x, y, "found the distress beacon");
     Ok(u64::try_from(x)? * 4_000_000 + u64::try_from(y)?)
 }
 
-fn find_beacon(sensors: &[Sensor], target_y: i32, max_val: i32) -> Option<(i32, i32)> {
-    let ranges = sensors
+/// Merges the sensors' ranges on a row into the sorted, disjoint, half open spans they cover
+fn covered_ranges(sensors: &[Sensor], target_y: i32) -> Vec<(i32, i32)> {
+    let changes = sensors
         .iter()
         .filter_map(|s| s.range_at(target_y))
-        .flat_map(|(s, e)| [(s, 1), (e, -1)])
+        .flat_map(|(s, e)| [(s, 1), (e + 1, -1)])
         .sorted_unstable()
         .group_by(|&(i, _)| i)
         .into_iter()
         .map(|(i, g)| (i, g.map(|(_, c)| c).sum::<i32>()))
         .filter(|(_, s)| *s != 0)
         .collect_vec();
-    let mut num = 0;
+
+    let mut ranges = Vec::new();
     let mut start = 0;
     let mut covered = 0;
-    for (x, change) in ranges {
-        // println!("x:{} c:{}", x, change);
-
+    for (x, change) in changes {
         if covered == 0 {
-            assert!(change > 0);
             start = x;
         }
         covered += change;
         if covered == 0 {
-            assert!(change < 0);
-
-            num += x.min(max_val) - start.max(0) + 1;
-            start = 0;
+            ranges.push((start, x));
         }
     }
-    if num == max_val {
-        for x in 0..max_val {
-            if sensors.iter().all(|s| {
-                if let Some((s, e)) = s.range_at(target_y) {
-                    !(s <= x && e >= x)
-                } else {
-                    true
-                }
-            }) {
-                return Some((x, target_y));
-            }
-        }
+    ranges
+}
+
+/// Finds the one spot between 0 and `max_val` on a row that no sensor reaches, if there is
+/// exactly one
+fn find_beacon(sensors: &[Sensor], target_y: i32, max_val: i32) -> Option<(i32, i32)> {
+    let ranges = covered_ranges(sensors, target_y);
+    let num: i32 = ranges
+        .iter()
+        .map(|&(start, end)| (end.min(max_val + 1) - start.max(0)).max(0))
+        .sum();
+
+    if num != max_val {
         return None;
     }
-    None
+    (0..=max_val)
+        .find(|&x| !ranges.iter().any(|&(start, end)| (start..end).contains(&x)))
+        .map(|x| (x, target_y))
+}
+
+/// Four sensors that leave exactly one spot in the search area unreached, along with `size - 4`
+/// more that don't reach it either
+fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+    let others = size
+        .checked_sub(4)
+        .ok_or_else(|| anyhow::anyhow!("At least four sensors are needed to hide the beacon"))?;
+    let (hx, hy) = (rng.range(0..=4_000_000), rng.range(0..=4_000_000));
+
+    // Each corner sensor reaches everything in its quarter of the search area but the hidden spot
+    let d = 4_000_001;
+    let mut sensors = [(-1, -1), (-1, 1), (1, -1), (1, 1)]
+        .map(|(dx, dy)| ((hx + dx * d, hy + dy * d), (hx + dx, hy)))
+        .to_vec();
+    for _ in 0..others {
+        let (sx, sy): (i32, i32) = (rng.range(0..=4_000_000), rng.range(0..=4_000_000));
+        let reach = i32::try_from(sx.abs_diff(hx) + sy.abs_diff(hy))? - 1;
+        let across = rng.range(0..=reach.max(0));
+        let (bx, by) = (across, reach.max(0) - across);
+        let beacon = match rng.range(0..=3) {
+            0 => (sx + bx, sy + by),
+            1 => (sx - bx, sy + by),
+            2 => (sx + bx, sy - by),
+            _ => (sx - bx, sy - by),
+        };
+        // A sensor on the hidden spot would have found it, so move it over one
+        let sensor = if reach < 0 { (sx + 1, sy) } else { (sx, sy) };
+        sensors.push((sensor, if reach < 0 { sensor } else { beacon }));
+    }
+    rng.shuffle(&mut sensors);
+
+    Ok(sensors
+        .iter()
+        .map(|((sx, sy), (bx, by))| {
+            format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}")
+        })
+        .join("\n"))
 }
 
 mod parser {
@@ -181,18 +245,115 @@ mod parser {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
     const EXAMPLE: &str = include_str!("../resources/example15.txt");
 
     #[test]
     fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE, 10).unwrap(), 26);
+        assert_eq!(part_a(&parse(EXAMPLE)?, 10)?, 26);
         Ok(())
     }
 
     #[test]
     fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE, 20).unwrap(), 56000011);
+        assert_eq!(part_b(&parse(EXAMPLE)?, 20)?, 56_000_011);
         Ok(())
     }
+
+    /// A sensor's location and its closest beacon, as the input lists them
+    type Report = ((i32, i32), (i32, i32));
+
+    fn distance((ax, ay): (i32, i32), (bx, by): (i32, i32)) -> i32 {
+        (ax - bx).abs() + (ay - by).abs()
+    }
+
+    fn covered(sensors: &[Sensor], p: (i32, i32)) -> bool {
+        sensors
+            .iter()
+            .any(|s| distance(s.location, p) <= s.distance)
+    }
+
+    fn to_input(sensors: &[Report]) -> String {
+        sensors
+            .iter()
+            .map(|((sx, sy), (bx, by))| {
+                format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}")
+            })
+            .join("\n")
+    }
+
+    fn point() -> impl Strategy<Value = (i32, i32)> {
+        (-10..=30, -10..=30)
+    }
+
+    /// Surrounds `hidden` with sensors until it is the only spot in the square they don't reach
+    fn hide_beacon(max_val: i32, hidden: (i32, i32), mut sensors: Vec<Report>) -> Vec<Report> {
+        sensors.retain(|&(s, b)| distance(s, b) < distance(s, hidden));
+        for y in 0..=max_val {
+            for x in 0..=max_val {
+                let reached = sensors
+                    .iter()
+                    .any(|&(s, b)| distance(s, (x, y)) <= distance(s, b));
+                if (x, y) != hidden && !reached {
+                    let d = distance((x, y), hidden) - 1;
+                    sensors.push(((x, y), (x + d, y)));
+                }
+            }
+        }
+        sensors
+    }
+
+    proptest! {
+        #[test]
+        fn counts_row_like_brute_force(
+            sensors in prop::collection::vec((point(), point()), 1..8),
+            target_y in -10..=30,
+        ) {
+            let sensors = parse(&to_input(&sensors)).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let reach = sensors.iter().map(|s| s.distance).max().unwrap_or(0);
+            let expected = (-10 - reach..=30 + reach)
+                .filter(|&x| {
+                    covered(&sensors, (x, target_y))
+                        && !sensors.iter().any(|s| s.beacon == (x, target_y))
+                })
+                .count();
+
+            prop_assert_eq!(
+                part_a(&sensors, target_y).map_err(|e| TestCaseError::fail(e.to_string()))?,
+                u32::try_from(expected)?
+            );
+        }
+
+        #[test]
+        fn finds_gap_in_row_like_brute_force(
+            sensors in prop::collection::vec((point(), point()), 1..8),
+            max_val in 1..=20,
+            target_y in 0..=20,
+        ) {
+            let sensors = parse(&to_input(&sensors)).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let gaps = (0..=max_val)
+                .filter(|&x| !covered(&sensors, (x, target_y)))
+                .map(|x| (x, target_y))
+                .collect_vec();
+            let expected = (gaps.len() == 1).then(|| gaps[0]);
+
+            prop_assert_eq!(find_beacon(&sensors, target_y, max_val), expected);
+        }
+
+        #[test]
+        fn finds_the_only_gap(
+            sensors in prop::collection::vec((point(), point()), 0..6),
+            (max_val, hidden) in (1..=20).prop_flat_map(|m| (Just(m), (0..=m, 0..=m))),
+        ) {
+            let input = to_input(&hide_beacon(max_val, hidden, sensors));
+            let sensors = parse(&input).map_err(|e| TestCaseError::fail(e.to_string()))?;
+
+            prop_assert_eq!(
+                part_b(&sensors, max_val).map_err(|e| TestCaseError::fail(e.to_string()))?,
+                u64::try_from(hidden.0)? * 4_000_000 + u64::try_from(hidden.1)?
+            );
+        }
+    }
 }
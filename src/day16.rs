@@ -1,42 +1,90 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
+use anyhow::anyhow;
 use itertools::Itertools;
+use tracing::debug;
 
-use crate::final_parser;
+use crate::{cancel::Token, final_parser, generate::Rng, Answer, Solution};
 
-const INPUT: &str = include_str!("../resources/input16.txt");
+pub struct Day16;
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+    const TITLE: &'static str = "Proboscidea Volcanium";
+    // The valves are kept as bits of a u64, and the search slows down quickly with more of them
+    const GENERATE_SIZES: RangeInclusive<usize> = 2..=64;
+
+    type Input<'a> = Valves<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        generate(size, rng)
+    }
+}
+
+/// The valves by name, along with the bit each one sets in a mask of opened valves
+#[derive(Debug)]
+pub struct Valves<'a> {
+    valves: HashMap<&'a str, Valve<'a>>,
+    v_idx: HashMap<&'a str, usize>,
 }
 
-pub fn solve_part_b() -> Result<u32, anyhow::Error> {
-    part_b(INPUT)
+fn parse(input: &str) -> Result<Valves<'_>, anyhow::Error> {
+    let valves = final_parser(parser::parse)(input)?;
+    let valves: HashMap<_, _> = valves.into_iter().map(|v| (v.name, v)).collect();
+
+    if !valves.contains_key("AA") {
+        return Err(anyhow!("No valve AA to start from"));
+    }
+    if let Some(t) = valves
+        .values()
+        .flat_map(|v| &v.tunnels)
+        .find(|t| !valves.contains_key(*t))
+    {
+        return Err(anyhow!("Tunnel leads to unknown valve {t}"));
+    }
+    if valves.len() > 64 {
+        return Err(anyhow!("Too many valves, at most 64 are supported"));
+    }
+    // Every set of opened valves is a state, so the search grows exponentially with these
+    if valves.values().filter(|v| v.flow_rate > 0).count() > 16 {
+        return Err(anyhow!(
+            "Too many valves with a flow, at most 16 are supported"
+        ));
+    }
+
+    let v_idx = valves.keys().enumerate().map(|(i, &v)| (v, i)).collect();
+    Ok(Valves { valves, v_idx })
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let solutions = calc_solutions(input, 30)?;
+fn part_a(valves: &Valves<'_>) -> Result<u32, anyhow::Error> {
+    let solutions = calc_solutions(valves, 30)?;
     Ok(solutions.values().copied().max().unwrap_or(0))
 }
 
-fn calc_solutions(
-    input: &str,
+fn calc_solutions<'a>(
+    Valves { valves, v_idx }: &Valves<'a>,
     max_time: usize,
-) -> Result<HashMap<(&str, u64), u32>, anyhow::Error> {
-    let valves = final_parser(parser::parse)(input)?;
-    let valves: HashMap<_, _> = valves.into_iter().map(|v| (v.name, v)).collect();
-    let v_idx: HashMap<_, _> = valves.keys().enumerate().map(|(i, &v)| (v, i)).collect();
+) -> Result<HashMap<(&'a str, u64), u32>, anyhow::Error> {
+    let token = Token::current();
     let flows: HashMap<_, _> = v_idx
         .iter()
         .map(|(&s, &i)| (1u64 << i, valves[s].flow_rate))
         .collect();
-    let mut max_preasure = HashMap::new();
-    let v = &valves["AA"];
-    max_preasure.insert((v.name, 1u64 << v_idx[v.name]), 0);
-    for &t in &v.tunnels {
-        max_preasure.insert((t, 0), 0);
-    }
-    for _ in 0..(max_time - 1) {
+    let mut max_preasure = HashMap::from([((valves["AA"].name, 0), 0)]);
+    for minute in 1..=max_time {
+        token.check()?;
         let mut next_max_preasure: HashMap<(&str, u64), u32> = HashMap::new();
         for ((v_name, opened), presure) in max_preasure {
             let v = &valves[v_name];
@@ -73,12 +121,17 @@ fn calc_solutions(
             }
         }
         max_preasure = next_max_preasure;
+        debug!(
+            minute,
+            states = max_preasure.len(),
+            "searched another minute"
+        );
     }
     Ok(max_preasure)
 }
 
-fn part_b(input: &str) -> Result<u32, anyhow::Error> {
-    let solutions = calc_solutions(input, 26)?;
+fn part_b(valves: &Valves<'_>) -> Result<u32, anyhow::Error> {
+    let solutions = calc_solutions(valves, 26)?;
 
     let groups = solutions
         .iter()
@@ -89,7 +142,6 @@ fn part_b(input: &str) -> Result<u32, anyhow::Error> {
     let max_by_open = groups
         .into_iter()
         .map(|(o, p)| (o, p.map(|(_, p)| p).max().unwrap_or(0)))
-        .filter(|&(o, p)| o != 0 && p != 0)
         .collect_vec();
 
     let result = max_by_open
@@ -110,12 +162,62 @@ fn part_b(input: &str) -> Result<u32, anyhow::Error> {
 }
 
 #[derive(Debug)]
-struct Valve<'a> {
+pub struct Valve<'a> {
     name: &'a str,
     flow_rate: u32,
     tunnels: Vec<&'a str>,
 }
 
+/// `size` valves joined by tunnels, about a quarter of them with a flow
+fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+    if !Day16::GENERATE_SIZES.contains(&size) {
+        return Err(anyhow!("Between 2 and 64 valves are supported"));
+    }
+    let letter = |n: usize| char::from(b'A' + u8::try_from(n).unwrap_or(0));
+    let name = |v: usize| format!("{}{}", letter(v / 26), letter(v % 26));
+
+    let mut tunnels = vec![Vec::new(); size];
+    let mut join = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    // Join each valve to an earlier one so every valve can be reached, then add some shortcuts
+    for v in 1..size {
+        join(v, rng.range(0..=v - 1));
+    }
+    for _ in 0..size / 2 {
+        join(rng.range(0..=size - 1), rng.range(0..=size - 1));
+    }
+
+    let mut flowing = (1..size).collect_vec();
+    rng.shuffle(&mut flowing);
+    flowing.truncate((size / 4).clamp(1, 16));
+
+    Ok((0..size)
+        .map(|v| {
+            let flow = if flowing.contains(&v) {
+                rng.range(1..=25)
+            } else {
+                0
+            };
+            let to = tunnels[v].iter().map(|&t| name(t)).join(", ");
+            if tunnels[v].len() == 1 {
+                format!(
+                    "Valve {} has flow rate={flow}; tunnel leads to valve {to}",
+                    name(v)
+                )
+            } else {
+                format!(
+                    "Valve {} has flow rate={flow}; tunnels lead to valves {to}",
+                    name(v)
+                )
+            }
+        })
+        .join("\n"))
+}
+
 mod parser {
     use super::Valve;
     use nom::{
@@ -128,7 +230,7 @@ mod parser {
         IResult,
     };
 
-    pub(super) fn parse(input: &str) -> IResult<&str, Vec<Valve>> {
+    pub(super) fn parse(input: &str) -> IResult<&str, Vec<Valve<'_>>> {
         separated_list1(
             line_ending,
             map(
@@ -156,18 +258,136 @@ mod parser {
 
 #[cfg(test)]
 mod tests {
+    use proptest::{prelude::*, sample::Index};
+
     use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example16.txt");
 
-    #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 1651);
-        Ok(())
+    /// A connected cave of valves named `AA`, `BB`, ... with `AA` first
+    fn cave() -> impl Strategy<Value = (Vec<u32>, Vec<Vec<usize>>)> {
+        (2..=7_usize)
+            .prop_flat_map(|n| {
+                (
+                    prop::collection::vec(0..=25_u32, n),
+                    prop::collection::vec(any::<Index>(), n - 1),
+                    prop::collection::vec((any::<Index>(), any::<Index>()), 0..n),
+                )
+            })
+            .prop_map(|(flows, parents, extra)| {
+                let n = flows.len();
+                let mut tunnels = vec![Vec::new(); n];
+                let spanning = parents
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (i + 1, p.index(i + 1)));
+                let extra = extra.iter().map(|(a, b)| (a.index(n), b.index(n)));
+                for (a, b) in spanning.chain(extra) {
+                    if a != b && !tunnels[a].contains(&b) {
+                        tunnels[a].push(b);
+                        tunnels[b].push(a);
+                    }
+                }
+                (flows, tunnels)
+            })
+    }
+
+    fn name(i: usize) -> String {
+        let c = char::from(b'A' + u8::try_from(i).unwrap_or(0));
+        format!("{c}{c}")
+    }
+
+    fn to_input(flows: &[u32], tunnels: &[Vec<usize>]) -> String {
+        flows
+            .iter()
+            .zip(tunnels)
+            .enumerate()
+            .map(|(i, (flow, t))| {
+                let leads = if t.len() == 1 {
+                    "tunnel leads to valve"
+                } else {
+                    "tunnels lead to valves"
+                };
+                let t = t.iter().map(|&v| name(v)).join(", ");
+                format!("Valve {} has flow rate={flow}; {leads} {t}", name(i))
+            })
+            .join("\n")
+    }
+
+    /// Tries every order of opening the valves in `closed`, walking the shortest way between them
+    fn most_pressure(
+        flows: &[u32],
+        distances: &[Vec<u32>],
+        at: usize,
+        time: u32,
+        closed: u64,
+    ) -> u32 {
+        (0..flows.len())
+            .filter(|&v| closed & (1 << v) != 0 && distances[at][v] + 1 < time)
+            .map(|v| {
+                let time = time - distances[at][v] - 1;
+                flows[v] * time + most_pressure(flows, distances, v, time, closed & !(1 << v))
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn distances(tunnels: &[Vec<usize>]) -> Vec<Vec<u32>> {
+        (0..tunnels.len())
+            .map(|from| {
+                let mut distance = vec![u32::MAX; tunnels.len()];
+                distance[from] = 0;
+                let mut queue = std::collections::VecDeque::from([from]);
+                while let Some(v) = queue.pop_front() {
+                    for &t in &tunnels[v] {
+                        if distance[t] == u32::MAX {
+                            distance[t] = distance[v] + 1;
+                            queue.push_back(t);
+                        }
+                    }
+                }
+                distance
+            })
+            .collect()
     }
 
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 1707);
-        Ok(())
+    proptest! {
+        // Each case runs the whole 26 and 30 minute search, which is slow in debug builds
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn part_a_matches_brute_force((flows, tunnels) in cave()) {
+            let input = to_input(&flows, &tunnels);
+            let valves = parse(&input)
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let worth_opening = (0..flows.len())
+                .filter(|&v| flows[v] > 0)
+                .fold(0, |m, v| m | (1 << v));
+
+            prop_assert_eq!(
+                part_a(&valves).map_err(|e| TestCaseError::fail(e.to_string()))?,
+                most_pressure(&flows, &distances(&tunnels), 0, 30, worth_opening)
+            );
+        }
+
+        #[test]
+        fn part_b_matches_brute_force((flows, tunnels) in cave()) {
+            let input = to_input(&flows, &tunnels);
+            let valves = parse(&input)
+                .map_err(|e| TestCaseError::fail(e.to_string()))?;
+            let distances = distances(&tunnels);
+            let worth_opening: u64 = (0..flows.len())
+                .filter(|&v| flows[v] > 0)
+                .fold(0, |m, v| m | (1 << v));
+            let expected = (0..=worth_opening)
+                .filter(|mine| mine & !worth_opening == 0)
+                .map(|mine| {
+                    most_pressure(&flows, &distances, 0, 26, mine)
+                        + most_pressure(&flows, &distances, 0, 26, worth_opening & !mine)
+                })
+                .max()
+                .unwrap_or(0);
+
+            let actual = part_b(&valves).map_err(|e| TestCaseError::fail(e.to_string()))?;
+            prop_assert_eq!(actual, expected);
+        }
     }
 }
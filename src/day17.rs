@@ -1,6 +1,14 @@
-use std::convert::identity;
+use std::{collections::HashMap, convert::identity};
 
-const INPUT: &str = include_str!("../resources/input17.txt");
+use tracing::debug;
+
+use crate::{cancel::Token, generate::Rng, Answer, ParseError, Solution};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Jet {
+    Left,
+    Right,
+}
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Shape {
@@ -40,23 +48,22 @@ impl Shape {
         y > 0 && self.is_clear((x, y - 1), field)
     }
 
-    pub fn try_jet(self, j: char, (x, y): (u32, u32), field: &[[bool; 7]]) -> (u32, u32) {
+    pub fn try_jet(self, j: Jet, (x, y): (u32, u32), field: &[[bool; 7]]) -> (u32, u32) {
         match j {
-            '<' => {
+            Jet::Left => {
                 if x != 0 && self.is_clear((x - 1, y), field) {
                     (x - 1, y)
                 } else {
                     (x, y)
                 }
             }
-            '>' => {
+            Jet::Right => {
                 if x + self.bound().0 < 7 && self.is_clear((x + 1, y), field) {
                     (x + 1, y)
                 } else {
                     (x, y)
                 }
             }
-            _ => panic!("No direction"),
         }
     }
 
@@ -70,147 +77,218 @@ impl Shape {
     }
 }
 
-pub fn solve_part_a() -> Result<u32, anyhow::Error> {
-    part_a(INPUT)
-}
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+    const TITLE: &'static str = "Pyroclastic Flow";
 
-pub fn solve_part_b() -> Result<u64, anyhow::Error> {
-    part_b(INPUT)
+    type Input<'a> = Vec<Jet>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error> {
+        parse(input)
+    }
+
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_a(input)?.into())
+    }
+
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error> {
+        Ok(part_b(input)?.into())
+    }
+
+    fn generate(size: usize, rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Ok(generate(size, rng))
+    }
 }
 
-fn part_a(input: &str) -> Result<u32, anyhow::Error> {
-    let mut field = Vec::new();
-    let mut first_free = 0;
-
-    let mut jets = input.chars().cycle();
-
-    //drop 2022 rocks
-    for (_, s) in (0..2022).zip(
-        [
-            Shape::HLine,
-            Shape::Cross,
-            Shape::L,
-            Shape::VLine,
-            Shape::Square,
-        ]
-        .into_iter()
-        .cycle(),
-    ) {
-        // println!("{} {:?}", i, s);
-        let (_, height) = s.bound();
+fn parse(input: &str) -> Result<Vec<Jet>, anyhow::Error> {
+    let jets = input
+        .char_indices()
+        .map(|(i, c)| match c {
+            '<' => Ok(Jet::Left),
+            '>' => Ok(Jet::Right),
+            _ => Err(ParseError::new(input, &input[i..], "expected < or >")),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if jets.is_empty() {
+        return Err(anyhow::anyhow!("No jets in input"));
+    }
+    Ok(jets)
+}
 
-        //init more field lines
-        for _ in field.len()..(first_free + 3 + height) as usize {
-            field.push([false; 7]);
-        }
+fn part_a(input: &[Jet]) -> Result<u32, anyhow::Error> {
+    let mut tower = Tower::default();
+    for rock in 0..2022 {
+        tower.drop(SHAPES[rock % SHAPES.len()], input)?;
+    }
 
-        let mut loc = (2, first_free + 3);
+    Ok(tower.first_free)
+}
 
-        for j in &mut jets {
-            // do jet
-            loc = s.try_jet(j, loc, &field);
+fn part_b(input: &[Jet]) -> Result<u64, anyhow::Error> {
+    height_after(input, 1_000_000_000_000)
+}
 
-            // do drop
-            if s.can_drop(loc, &field) {
-                loc = (loc.0, loc.1 - 1);
-            } else {
-                s.write(loc, &mut field);
-                first_free = calc_first_free(&field).try_into()?;
+/// The height of the tower after `rocks` rocks, extrapolated from the cycle its growth falls into
+fn height_after(input: &[Jet], rocks: u64) -> Result<u64, anyhow::Error> {
+    let (heights, size, offset) = find_cycle(input, Token::current())?;
+    debug!(size, offset, "the tower's growth repeats");
 
-                break;
-            }
-        }
+    // heights[n] is the tower height after n rocks, which grows the same way every `size` rocks
+    // from `offset`
+    if let Some(&height) = usize::try_from(rocks).ok().and_then(|r| heights.get(r)) {
+        return Ok(height as u64);
     }
-    //measure height
+    let cycles = (rocks - offset as u64) / size as u64;
+    let remainder = usize::try_from((rocks - offset as u64) % size as u64)?;
+    let cycle_height = (heights[offset + size] - heights[offset]) as u64;
 
-    Ok(calc_first_free(&field).try_into()?)
+    Ok(heights[offset + remainder] as u64 + cycles * cycle_height)
 }
 
-fn calc_first_free(field: &[[bool; 7]]) -> usize {
-    field
-        .iter()
-        .enumerate()
-        .rev()
-        .find(|(_, &r)| r.into_iter().any(identity))
-        .map_or(0, |(i, _)| i + 1)
+const SHAPES: [Shape; 5] = [
+    Shape::HLine,
+    Shape::Cross,
+    Shape::L,
+    Shape::VLine,
+    Shape::Square,
+];
+
+/// How far below the top of the tower the skyline is looked at when comparing states. Rocks that
+/// land lower than this are too deep to matter in practice.
+const PROFILE_DEPTH: usize = 32;
+
+/// The rocks that have come to rest, and where in the jet pattern the next one starts
+#[derive(Default)]
+struct Tower {
+    field: Vec<[bool; 7]>,
+    first_free: u32,
+    jet: usize,
 }
 
-fn part_b(input: &str) -> Result<u64, anyhow::Error> {
-    let mut field = Vec::new();
-    let mut first_free = 0;
-
-    let mut jets = input.chars().cycle();
-
-    for (_, s) in (0..10_000).zip(
-        [
-            Shape::HLine,
-            Shape::Cross,
-            Shape::L,
-            Shape::VLine,
-            Shape::Square,
-        ]
-        .into_iter()
-        .cycle(),
-    ) {
-        // println!("{} {:?}", i, s);
+impl Tower {
+    /// Drops one rock, pushed by the jets from where the last one left off until it comes to rest
+    fn drop(&mut self, s: Shape, jets: &[Jet]) -> Result<(), anyhow::Error> {
         let (_, height) = s.bound();
 
         //init more field lines
-        for _ in field.len()..(first_free + 3 + height) as usize {
-            field.push([false; 7]);
+        for _ in self.field.len()..(self.first_free + 3 + height) as usize {
+            self.field.push([false; 7]);
         }
 
-        let mut loc = (2, first_free + 3);
+        let mut loc = (2, self.first_free + 3);
 
-        for j in &mut jets {
+        loop {
             // do jet
-            loc = s.try_jet(j, loc, &field);
+            loc = s.try_jet(jets[self.jet], loc, &self.field);
+            self.jet = (self.jet + 1) % jets.len();
 
             // do drop
-            if s.can_drop(loc, &field) {
+            if s.can_drop(loc, &self.field) {
                 loc = (loc.0, loc.1 - 1);
             } else {
-                s.write(loc, &mut field);
-                first_free = calc_first_free(&field).try_into()?;
-
-                break;
+                s.write(loc, &mut self.field);
+                self.first_free = calc_first_free(&self.field).try_into()?;
+                return Ok(());
             }
         }
     }
 
-    find_repeat(&mut field);
-    // modify above to provide heights
-    // look up block count vs height
-
-    Ok(calc_first_free(&field).try_into()?)
+    /// How far below the top of the tower the highest rock in each column is, up to
+    /// `PROFILE_DEPTH`
+    fn profile(&self) -> [usize; 7] {
+        let top = self.first_free as usize;
+        std::array::from_fn(|x| {
+            (0..PROFILE_DEPTH.min(top))
+                .find(|&d| self.field[top - 1 - d][x])
+                .unwrap_or(PROFILE_DEPTH)
+        })
+    }
 }
 
-fn find_repeat(mut field: &[[bool; 7]]) -> Option<(usize, usize)> {
-    for size in 5..10000 {
-        for offset in 1..10000 {
-            if (0..size).all(|i| field[i + offset] == field[size + i + offset]) {
-                println!("size {}, offset {}", size, offset);
-                return Some((size, offset));
+/// Drops rocks until the next shape, the place in the jet pattern and the top of the tower are
+/// all the same as they were before an earlier rock, from which point the tower grows the same
+/// way for ever. As only the top of the tower is compared, the cycle is only taken once the
+/// tower has grown the same way over it a second time. Returns the height after each rock so
+/// far, how many rocks the cycle takes and how many rocks come before it, giving up if it runs
+/// out of time.
+fn find_cycle(jets: &[Jet], token: Token) -> Result<(Vec<usize>, usize, usize), anyhow::Error> {
+    // Puzzle inputs repeat within a few thousand rocks, this only stops a search that never does
+    let max_rocks = 8 * SHAPES.len() * jets.len() + 10_000;
+
+    let mut tower = Tower::default();
+    let mut heights = vec![0];
+    let mut seen = HashMap::new();
+    // The start and size of a cycle waiting to repeat
+    let mut candidate = None;
+    for rock in 0..max_rocks {
+        token.check()?;
+        if let Some((start, size)) = candidate {
+            if rock == start + 2 * size {
+                if repeats(&heights, start, size) {
+                    return Ok((heights, size, start));
+                }
+                candidate = None;
             }
         }
+        let shape = rock % SHAPES.len();
+        if let Some(start) = seen.insert((shape, tower.jet, tower.profile()), rock) {
+            candidate = candidate.or(Some((start, rock - start)));
+        }
+        tower.drop(SHAPES[shape], jets)?;
+        heights.push(tower.first_free as usize);
     }
-    None
+    Err(anyhow::anyhow!("No repeating pattern found"))
+}
+
+/// Whether the tower grew the same way over the `size` rocks after `start + size` as over the
+/// `size` rocks after `start`
+fn repeats(heights: &[usize], start: usize, size: usize) -> bool {
+    (0..=size).all(|i| {
+        heights[start + size + i] - heights[start + size] == heights[start + i] - heights[start]
+    })
+}
+
+fn calc_first_free(field: &[[bool; 7]]) -> usize {
+    field
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, &r)| r.into_iter().any(identity))
+        .map_or(0, |(i, _)| i + 1)
+}
+
+/// `size` jets of hot gas
+fn generate(size: usize, rng: &mut Rng) -> String {
+    (0..size)
+        .map(|_| if rng.one_in(2) { '<' } else { '>' })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    const EXAMPLE: &str = include_str!("../resources/example17.txt");
 
     #[test]
-    fn example_a() -> Result<(), anyhow::Error> {
-        assert_eq!(part_a(EXAMPLE).unwrap(), 3068);
-        Ok(())
-    }
-
-    #[test]
-    fn example_b() -> Result<(), anyhow::Error> {
-        assert_eq!(part_b(EXAMPLE).unwrap(), 1514285714288);
+    fn extrapolated_heights_match_dropping_every_rock() -> Result<(), anyhow::Error> {
+        const ROCKS: usize = 5_000;
+
+        for seed in 0..5 {
+            let jets = parse(&generate(40, &mut Rng::new(seed)))?;
+            // Found early enough that the height is extrapolated rather than simulated
+            assert!(find_cycle(&jets, Token::default())?.0.len() < ROCKS);
+            let mut tower = Tower::default();
+            for rock in 0..ROCKS {
+                tower.drop(SHAPES[rock % SHAPES.len()], &jets)?;
+            }
+            assert_eq!(
+                height_after(&jets, ROCKS as u64)?,
+                u64::from(tower.first_free),
+                "seed {seed}"
+            );
+        }
         Ok(())
     }
 }
@@ -0,0 +1,87 @@
+use std::ops::RangeInclusive;
+
+/// A small seeded random number generator, so that the same seed always generates the same input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// The next number from a `SplitMix64` sequence
+    pub const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in the range, or its start if the range is empty
+    pub fn range<T>(&mut self, range: RangeInclusive<T>) -> T
+    where
+        T: Copy + TryInto<i128> + TryFrom<i128>,
+    {
+        let (start, end) = range.into_inner();
+        let (Ok(low), Ok(high)) = (start.try_into(), end.try_into()) else {
+            return start;
+        };
+        let Ok(span) = u128::try_from(high - low) else {
+            return start;
+        };
+        let offset = u128::from(self.next_u64()) % (span + 1);
+        i128::try_from(offset)
+            .ok()
+            .and_then(|offset| T::try_from(low + offset).ok())
+            .unwrap_or(start)
+    }
+
+    /// True one time in `n`
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.range(1..=n) == 1
+    }
+
+    /// A random item from a slice, or `None` if it is empty
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        let last = items.len().checked_sub(1)?;
+        items.get(self.range(0..=last))
+    }
+
+    /// Puts the items in a random order
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..=i));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_numbers() {
+        let mut a = Rng::new(18);
+        let mut b = Rng::new(18);
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!((-3..=3).contains(&rng.range(-3..=3)));
+            assert!((5..=5).contains(&rng.range(5_u32..=5)));
+            assert_eq!(rng.range(u64::MAX..=u64::MAX), u64::MAX);
+        }
+        assert!((0..1000).any(|_| rng.range(0..=1) == 0));
+        assert!((0..1000).any(|_| rng.range(0..=1) == 1));
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 7..=3;
+        assert_eq!(rng.range(empty), 7);
+        assert_eq!(rng.choose::<u32>(&[]), None);
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::Path, time::Duration};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::Part;
+
+/// Where the benchmark history is kept unless told otherwise
+pub const DEFAULT_PATH: &str = "benchmarks.toml";
+
+const HEADER: &str = "\
+# Median times of each part, recorded by `runner --record` and compared against by
+# `runner perf-diff`. Times are only comparable between runs on the same machine.
+
+";
+
+/// Every recorded timing, oldest first
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default, rename = "timing")]
+    timings: Vec<Timing>,
+}
+
+/// The median time of one part, at one commit on one machine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timing {
+    pub commit: String,
+    pub machine: String,
+    /// Seconds since the Unix epoch
+    pub recorded: u64,
+    pub day: u32,
+    pub part: Part,
+    pub median_ns: u64,
+}
+
+impl History {
+    /// Loads the history, which is empty until something is recorded
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content)
+                .with_context(|| format!("invalid benchmark history in {}", path.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| {
+                format!("unable to read benchmark history from {}", path.display())
+            }),
+        }
+    }
+
+    pub fn parse(content: &str) -> Result<Self, anyhow::Error> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let content = format!("{HEADER}{}", toml::to_string_pretty(self)?);
+        fs::write(path, content)
+            .with_context(|| format!("unable to write benchmark history to {}", path.display()))
+    }
+
+    pub fn record(&mut self, timings: impl IntoIterator<Item = Timing>) {
+        self.timings.extend(timings);
+    }
+
+    /// The commit most recently recorded on a machine
+    #[must_use]
+    pub fn latest_commit(&self, machine: &str) -> Option<&str> {
+        self.timings
+            .iter()
+            .rev()
+            .find(|t| t.machine == machine)
+            .map(|t| t.commit.as_str())
+    }
+
+    /// The most recent median of each day and part recorded for a commit on a machine, where the
+    /// commit can be shortened to any prefix of its hash
+    #[must_use]
+    pub fn baseline(&self, commit: &str, machine: &str) -> BTreeMap<(u32, Part), Duration> {
+        self.timings
+            .iter()
+            .filter(|t| t.machine == machine && same_commit(&t.commit, commit))
+            .map(|t| ((t.day, t.part), Duration::from_nanos(t.median_ns)))
+            .collect()
+    }
+}
+
+/// Whether two hashes are of the same commit, either of them shortened
+fn same_commit(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    !short.is_empty() && long.starts_with(short)
+}
+
+/// How much slower, as a percentage, a part has become since the baseline. Getting faster is a
+/// negative change.
+#[must_use]
+pub fn change(baseline: Duration, current: Duration) -> f64 {
+    if baseline.is_zero() {
+        0.0
+    } else {
+        (current.as_secs_f64() / baseline.as_secs_f64() - 1.0) * 100.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing(commit: &str, machine: &str, day: u32, median_ns: u64) -> Timing {
+        Timing {
+            commit: commit.to_owned(),
+            machine: machine.to_owned(),
+            recorded: 0,
+            day,
+            part: Part::A,
+            median_ns,
+        }
+    }
+
+    #[test]
+    fn baselines() {
+        let mut history = History::default();
+        history.record([
+            timing("aaaa1111", "laptop", 1, 100),
+            timing("bbbb2222", "laptop", 1, 200),
+            timing("bbbb2222", "laptop", 1, 150),
+            timing("cccc3333", "server", 1, 50),
+        ]);
+
+        assert_eq!(history.latest_commit("laptop"), Some("bbbb2222"));
+        assert_eq!(history.latest_commit("server"), Some("cccc3333"));
+        assert_eq!(history.latest_commit("desktop"), None);
+
+        let baseline = history.baseline("bbbb", "laptop");
+        assert_eq!(
+            baseline.get(&(1, Part::A)),
+            Some(&Duration::from_nanos(150))
+        );
+        assert!(history.baseline("cccc", "laptop").is_empty());
+        assert!(history.baseline("", "laptop").is_empty());
+    }
+
+    #[test]
+    fn changes() {
+        let ms = Duration::from_millis;
+        assert!((change(ms(100), ms(125)) - 25.0).abs() < 1e-9);
+        assert!((change(ms(100), ms(50)) + 50.0).abs() < 1e-9);
+        assert!(change(Duration::ZERO, ms(5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn saves_and_reloads() -> Result<(), anyhow::Error> {
+        let path = std::env::temp_dir().join(format!("aoc-history-{}.toml", std::process::id()));
+        assert_eq!(History::load(&path)?, History::default());
+
+        let mut history = History::default();
+        history.record([timing("aaaa1111", "laptop", 16, 1_400_000_000)]);
+        history.save(&path)?;
+        let reloaded = History::load(&path);
+        fs::remove_file(&path)?;
+
+        assert_eq!(reloaded?, history);
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use std::{
+    borrow::Cow,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context};
+
+/// Directory the puzzle inputs are read from unless told otherwise
+pub const DEFAULT_DIR: &str = "resources";
+
+/// Name of the manifest of answers expected from the examples, kept in the same directory as them
+pub const EXAMPLES_MANIFEST: &str = "examples.toml";
+
+/// Locates puzzle inputs, named `inputNN.txt`, within a directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inputs {
+    dir: PathBuf,
+}
+
+impl Default for Inputs {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIR)
+    }
+}
+
+impl Inputs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Uses the directory in `AOC_INPUTS` if set, otherwise the default
+    #[must_use]
+    pub fn from_env() -> Self {
+        std::env::var_os("AOC_INPUTS").map_or_else(Self::default, Self::new)
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    #[must_use]
+    pub fn path(&self, day: u32) -> PathBuf {
+        self.dir.join(file_name(day))
+    }
+
+    /// The path of a day's input if it is already in the directory
+    #[must_use]
+    pub fn cached(&self, day: u32) -> Option<PathBuf> {
+        let path = self.path(day);
+        fs::metadata(&path)
+            .is_ok_and(|m| m.is_file() && m.len() > 0)
+            .then_some(path)
+    }
+
+    /// Reads the input for a day, falling back to the embedded copy when built with `embedded-inputs`
+    pub fn load(&self, day: u32) -> Result<String, anyhow::Error> {
+        let path = self.path(day);
+        match fs::read_to_string(&path) {
+            Ok(input) => non_empty(input, &path),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => embedded(day)
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow!("no input for day {day} at {}", path.display())),
+            Err(e) => {
+                Err(e).with_context(|| format!("unable to read input from {}", path.display()))
+            }
+        }
+    }
+}
+
+/// Name of a day's input file
+#[must_use]
+pub fn file_name(day: u32) -> String {
+    format!("input{day:02}.txt")
+}
+
+/// A digest of an input's normalised content, which answers are kept against
+///
+/// Answers are then only ever checked against the same input, wherever it was read from. It is
+/// 64-bit FNV-1a, which is stable across platforms and versions, written as hex.
+#[must_use]
+pub fn digest(input: &str) -> String {
+    let hash = normalise(input)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Reads an input from a file, or from stdin when the path is `-`
+pub fn read(path: &Path) -> Result<String, anyhow::Error> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .context("unable to read input from stdin")?;
+        non_empty(input, path)
+    } else {
+        let input = fs::read_to_string(path)
+            .with_context(|| format!("unable to read input from {}", path.display()))?;
+        non_empty(input, path)
+    }
+}
+
+/// Puts an input in the form every day is written against: no byte order mark, `\n` line endings
+/// and nothing after the last line
+#[must_use]
+pub fn normalise(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input).trim_end();
+    if input.contains('\r') {
+        Cow::Owned(input.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Splits an input into the blocks of lines between blank lines, whatever the line endings
+pub fn blocks(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(['\r', '\n']);
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (block, next) = rest
+            .match_indices('\n')
+            .find_map(|(i, _)| {
+                let after = &rest[i + 1..];
+                let next = after
+                    .strip_prefix('\n')
+                    .or_else(|| after.strip_prefix("\r\n"))?;
+                Some((&rest[..i], next))
+            })
+            .unwrap_or((rest, ""));
+        rest = next;
+        Some(block.trim_end_matches(['\r', '\n']))
+    })
+}
+
+fn non_empty(input: String, path: &Path) -> Result<String, anyhow::Error> {
+    if input.trim().is_empty() {
+        Err(anyhow!("input {} is empty", path.display()))
+    } else {
+        Ok(input)
+    }
+}
+
+#[cfg(not(feature = "embedded-inputs"))]
+const fn embedded(_day: u32) -> Option<&'static str> {
+    None
+}
+
+#[cfg(feature = "embedded-inputs")]
+const fn embedded(day: u32) -> Option<&'static str> {
+    Some(match day {
+        1 => include_str!("../resources/input01.txt"),
+        2 => include_str!("../resources/input02.txt"),
+        3 => include_str!("../resources/input03.txt"),
+        4 => include_str!("../resources/input04.txt"),
+        5 => include_str!("../resources/input05.txt"),
+        6 => include_str!("../resources/input06.txt"),
+        7 => include_str!("../resources/input07.txt"),
+        8 => include_str!("../resources/input08.txt"),
+        9 => include_str!("../resources/input09.txt"),
+        10 => include_str!("../resources/input10.txt"),
+        11 => include_str!("../resources/input11.txt"),
+        12 => include_str!("../resources/input12.txt"),
+        13 => include_str!("../resources/input13.txt"),
+        14 => include_str!("../resources/input14.txt"),
+        15 => include_str!("../resources/input15.txt"),
+        16 => include_str!("../resources/input16.txt"),
+        17 => include_str!("../resources/input17.txt"),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_from_dir() -> Result<(), anyhow::Error> {
+        let inputs = Inputs::new("resources");
+        assert_eq!(inputs.path(1), Path::new("resources/input01.txt"));
+        assert!(!inputs.load(1)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn normalises_inputs() {
+        assert_eq!(normalise("\u{feff}1\r\n2\r\n\r\n3\r\n\r\n"), "1\n2\n\n3");
+        assert_eq!(normalise("    [D]\n1 2 \n"), "    [D]\n1 2");
+        assert!(matches!(normalise("1\n2"), Cow::Borrowed("1\n2")));
+    }
+
+    #[test]
+    fn digests_normalised_content() {
+        assert_eq!(digest(""), "cbf29ce484222325");
+        assert_eq!(digest("a"), "af63dc4c8601ec8c");
+        assert_eq!(digest("\u{feff}1\r\n2\r\n"), digest("1\n2"));
+        assert_ne!(digest("1\n2"), digest("2\n1"));
+    }
+
+    #[test]
+    fn splits_blocks() {
+        for input in ["1\n2\n\n3\n\n\n4\n", "1\r\n2\r\n\r\n3\r\n\r\n\r\n4\r\n"] {
+            let blocks = blocks(input).map(|b| b.lines().collect::<Vec<_>>());
+            assert!(blocks.eq([vec!["1", "2"], vec!["3"], vec!["4"]]));
+        }
+        assert!(blocks("\n\n").next().is_none());
+    }
+
+    #[test]
+    fn rejects_empty_input() {
+        assert!(non_empty(" \n".to_owned(), Path::new("empty.txt")).is_err());
+    }
+}
@@ -1,5 +1,10 @@
 use nom::{error::Error, Parser};
 
+pub mod allocations;
+mod answer;
+pub mod answers;
+pub mod cancel;
+pub mod client;
 pub mod day01;
 pub mod day02;
 pub mod day03;
@@ -17,37 +22,224 @@ pub mod day14;
 pub mod day15;
 pub mod day16;
 pub mod day17;
+pub mod generate;
+pub mod history;
+pub mod inputs;
+pub mod ocr;
+mod parse_error;
+mod solution;
+pub mod svg;
+pub mod timing;
 
-#[cfg(windows)]
-const DOUBLE_LINE_ENDING: &str = "\r\n\r\n";
-#[cfg(not(windows))]
-const DOUBLE_LINE_ENDING: &str = "\n\n";
+pub use answer::Answer;
+pub use inputs::Inputs;
+pub use parse_error::ParseError;
+pub use solution::{
+    Alternative, DynSolution, NamedSolver, NoGenerator, Part, PartRun, Run, Solution, Solver,
+    DEFAULT_IMPLEMENTATION,
+};
 
+/// Every implemented day, in day order
+pub static SOLUTIONS: &[&dyn DynSolution] = &[
+    &day01::Day01,
+    &day02::Day02,
+    &day03::Day03,
+    &day04::Day04,
+    &day05::Day05,
+    &day06::Day06,
+    &day07::Day07,
+    &day08::Day08,
+    &day09::Day09,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+];
+
+#[must_use]
+pub fn solution(day: u32) -> Option<&'static dyn DynSolution> {
+    SOLUTIONS.iter().copied().find(|s| s.day() == day)
+}
+
+/// Runs a parser over the whole input, reporting where it failed or any input it left unparsed
 pub fn final_parser<'a, R>(
     mut parser: impl Parser<&'a str, R, Error<&'a str>>,
 ) -> impl FnMut(&'a str) -> Result<R, anyhow::Error> {
     move |input| {
-        let r = parser
+        let (remaining, r) = parser
             .parse(input)
-            .map(|(_, r)| r)
-            .map_err(nom::Err::<nom::error::Error<&str>>::to_owned)?;
-        Ok(r)
+            .map_err(|e| ParseError::from_nom(input, &e))?;
+
+        if remaining.trim().is_empty() {
+            Ok(r)
+        } else {
+            Err(unparsed(input, remaining, &mut parser).into())
+        }
     }
 }
 
+/// Checks that an iterator made with [`nom::combinator::iterator`] over `input` parsed all of it,
+/// using `item`, the parser it was made with, to explain why any remaining input didn't parse
 pub fn finish_parser_it<'a, O, F>(
-    parse_it: nom::combinator::ParserIterator<&'a str, nom::error::Error<&'a str>, F>,
+    input: &'a str,
+    parse_it: nom::combinator::ParserIterator<&'a str, Error<&'a str>, F>,
+    mut item: impl Parser<&'a str, O, Error<&'a str>>,
 ) -> Result<(), anyhow::Error>
 where
-    F: Parser<&'a str, O, nom::error::Error<&'a str>>,
+    F: Parser<&'a str, O, Error<&'a str>>,
 {
-    let (input, _) = parse_it
+    let (remaining, ()) = parse_it
         .finish()
-        .map_err(nom::Err::<nom::error::Error<&str>>::to_owned)?;
+        .map_err(|e| ParseError::from_nom(input, &e))?;
 
-    if input.trim().is_empty() {
+    if remaining.trim().is_empty() {
         Ok(())
     } else {
-        Err(anyhow::anyhow!(format!("unparsed data: {}", input)))
+        Err(unparsed(input, remaining, &mut item).into())
+    }
+}
+
+/// Moves a [`ParseError`] from parsing `fragment`, a slice of `source`, to where it is in
+/// `source`, leaving any other error as it is
+pub fn within<'a>(
+    source: &'a str,
+    fragment: &'a str,
+) -> impl Fn(anyhow::Error) -> anyhow::Error + 'a {
+    move |e| match e.downcast::<ParseError>() {
+        Ok(e) => e.within(source, fragment).into(),
+        Err(e) => e,
+    }
+}
+
+/// Parsers that backtrack, such as lists, stop at the start of the item that failed to parse, so
+/// parse it again to find out where and why it failed
+fn unparsed<'a, O>(
+    input: &'a str,
+    remaining: &'a str,
+    parser: &mut impl Parser<&'a str, O, Error<&'a str>>,
+) -> ParseError {
+    match parser.parse(remaining.trim_start()) {
+        Err(e) => ParseError::from_nom(input, &e),
+        Ok(_) => ParseError::unparsed(input, remaining),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_examples_solve_the_same() -> Result<(), anyhow::Error> {
+        for s in SOLUTIONS {
+            let example = std::fs::read_to_string(format!("resources/example{:02}.txt", s.day()))?;
+            let crlf = format!("\u{feff}{}\r\n", example.replace('\n', "\r\n"));
+            for part in Part::ALL {
+                // Searches the real input's 4,000,000 square area, finding nothing in the example
+                if (s.day(), part) == (15, Part::B) {
+                    continue;
+                }
+                let label = format!("day{:02}{part}", s.day());
+                match s.solve(&example, part) {
+                    Ok(answer) => assert_eq!(s.solve(&crlf, part)?, answer, "{label}"),
+                    Err(_) => assert!(s.solve(&crlf, part).is_err(), "{label}"),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether a day has no input generator yet, such as one just made by `runner new`
+    fn no_generator(s: &dyn DynSolution) -> bool {
+        s.generate(*s.generate_sizes().start(), 0)
+            .is_err_and(|e| e.is::<NoGenerator>())
+    }
+
+    #[test]
+    fn generated_inputs_solve() -> Result<(), anyhow::Error> {
+        for s in SOLUTIONS {
+            if no_generator(*s) {
+                continue;
+            }
+            for seed in 0..3 {
+                let input = s.generate(30, seed)?;
+                assert_eq!(s.generate(30, seed)?, input);
+                for part in Part::ALL {
+                    // Searches the same 4,000,000 square area whatever the number of sensors
+                    if (s.day(), part) == (15, Part::B) {
+                        continue;
+                    }
+                    let label = format!("day{:02}{part} seed {seed}", s.day());
+                    s.solve(&input, part).map_err(|e| e.context(label))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn generated_sizes_are_kept_in_range() -> Result<(), anyhow::Error> {
+        for s in SOLUTIONS {
+            if no_generator(*s) {
+                continue;
+            }
+            let sizes = s.generate_sizes();
+            s.generate(*sizes.start(), 0)?;
+            assert!(
+                s.generate(sizes.start() - 1, 0).is_err(),
+                "day{:02}",
+                s.day()
+            );
+            assert!(s.generate(sizes.end() + 1, 0).is_err(), "day{:02}", s.day());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn alternatives_agree_on_generated_inputs() -> Result<(), anyhow::Error> {
+        for s in SOLUTIONS {
+            for part in Part::ALL {
+                let implementations = s.implementations(part);
+                if implementations.len() == 1 || no_generator(*s) {
+                    continue;
+                }
+                for seed in 0..3 {
+                    let input = s.generate(30, seed)?;
+                    let expected = s.solve(&input, part)?;
+                    for implementation in &implementations[1..] {
+                        let label = format!("day{:02}{part} {implementation} seed {seed}", s.day());
+                        let answer = s
+                            .solve_with(&input, part, implementation)
+                            .map_err(|e| e.context(label.clone()))?;
+                        assert_eq!(answer, expected, "{label}");
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn examples_visualise() -> Result<(), anyhow::Error> {
+        let mut drawn = Vec::new();
+        for s in SOLUTIONS {
+            let example = std::fs::read_to_string(format!("resources/example{:02}.txt", s.day()))?;
+            if let Some(picture) = s.visualise(&example)? {
+                assert!(picture.starts_with("<svg"), "day{:02}", s.day());
+                assert!(picture.contains("<rect"), "day{:02}", s.day());
+                drawn.push(s.day());
+            }
+        }
+        assert_eq!(drawn, [12, 14]);
+        Ok(())
+    }
+
+    #[test]
+    fn registry_is_in_day_order() {
+        assert!(SOLUTIONS.windows(2).all(|w| w[0].day() < w[1].day()));
+        assert!(SOLUTIONS.iter().all(|s| (1..=25).contains(&s.day())));
     }
 }
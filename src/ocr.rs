@@ -0,0 +1,185 @@
+use anyhow::anyhow;
+use itertools::Itertools;
+
+/// A font as the letters it has and a picture of them, in the same order and with blank columns
+/// between them
+type Font = (&'static str, &'static str);
+
+/// The letters drawn 4 pixels wide and 6 high, as on day 10's CRT
+const SMALL: Font = (
+    "ABCEFGHIJKLOPRSUYZ",
+    "\
+.##..###...##..####.####..##..#..#.###....##.#..#.#.....##..###..###...###.#..#.#...#.####
+#..#.#..#.#..#.#....#....#..#.#..#..#......#.#.#..#....#..#.#..#.#..#.#....#..#.#...#....#
+#..#.###..#....###..###..#....####..#......#.##...#....#..#.#..#.#..#.#....#..#..#.#....#.
+####.#..#.#....#....#....#.##.#..#..#......#.#.#..#....#..#.###..###...##..#..#...#....#..
+#..#.#..#.#..#.#....#....#..#.#..#..#...#..#.#.#..#....#..#.#....#.#.....#.#..#...#...#...
+#..#.###...##..####.#.....###.#..#.###...##..#..#.####..##..#....#..#.###...##....#...####
+",
+);
+
+/// The letters drawn 6 pixels wide and 10 high, as in the sky of 2018's day 10
+const LARGE: Font = (
+    "ABCEFGHJKLNPRXZ",
+    "\
+..##...#####...####..######.######..####..#....#....###.#....#.#......#....#.#####..#####..#....#.######
+.#..#..#....#.#....#.#......#......#....#.#....#.....#..#...#..#......##...#.#....#.#....#.#....#......#
+#....#.#....#.#......#......#......#......#....#.....#..#..#...#......##...#.#....#.#....#..#..#.......#
+#....#.#....#.#......#......#......#......#....#.....#..#.#....#......#.#..#.#....#.#....#..#..#......#.
+#....#.#####..#......#####..#####..#......######.....#..##.....#......#.#..#.#####..#####....##......#..
+######.#....#.#......#......#......#..###.#....#.....#..##.....#......#..#.#.#......#..#.....##.....#...
+#....#.#....#.#......#......#......#....#.#....#.....#..#.#....#......#..#.#.#......#...#...#..#...#....
+#....#.#....#.#......#......#......#....#.#....#.#...#..#..#...#......#...##.#......#...#...#..#..#.....
+#....#.#....#.#....#.#......#......#...##.#....#.#...#..#...#..#......#...##.#......#....#.#....#.#.....
+#....#.#####...####..######.#.......###.#.#....#..###...#....#.######.#....#.#......#....#.#....#.######
+",
+);
+
+/// A picture as rows of lit pixels
+type Pixels = Vec<Vec<bool>>;
+
+/// Reads the letters in a `#`/`.` picture drawn in either font
+///
+/// The font is picked by the height of the picture once any blank rows around it are removed, and
+/// letters are told apart by the blank columns between them.
+pub fn read(image: &str) -> Result<String, anyhow::Error> {
+    let picture = pixels(image)?;
+    let (letters, font) = match picture.len() {
+        6 => SMALL,
+        10 => LARGE,
+        height => {
+            return Err(anyhow!(
+                "Letters are 6 or 10 pixels high, not {height} like this picture"
+            ))
+        }
+    };
+
+    let font = glyphs(&pixels(font)?);
+    glyphs(&picture)
+        .into_iter()
+        .map(|(column, glyph)| {
+            letters
+                .chars()
+                .zip(&font)
+                .find(|(_, (_, g))| *g == glyph)
+                .map(|(c, _)| c)
+                .ok_or_else(|| anyhow!("Unknown letter at column {column}:\n{}", draw(&glyph)))
+        })
+        .collect()
+}
+
+/// Parses a picture, dropping the blank rows above and below it
+fn pixels(image: &str) -> Result<Pixels, anyhow::Error> {
+    let rows: Pixels = image
+        .lines()
+        .map(|line| {
+            line.chars()
+                .map(|c| match c {
+                    '#' => Ok(true),
+                    '.' | ' ' => Ok(false),
+                    _ => Err(anyhow!("Unexpected '{c}' in picture, expected '#' or '.'")),
+                })
+                .collect()
+        })
+        .try_collect()?;
+
+    let lit = |row: &&Vec<bool>| row.contains(&true);
+    let first = rows.iter().position(|row| lit(&row)).unwrap_or(0);
+    let last = rows.iter().rposition(|row| lit(&row)).unwrap_or(0);
+    Ok(rows
+        .into_iter()
+        .skip(first)
+        .take(last + 1 - first)
+        .collect())
+}
+
+/// Splits a picture into its letters at the blank columns, each with the column it starts at and
+/// trimmed to the columns it lights
+fn glyphs(pixels: &[Vec<bool>]) -> Vec<(usize, Pixels)> {
+    let width = pixels.iter().map(Vec::len).max().unwrap_or(0);
+    let lit = |x: usize| pixels.iter().any(|row| row.get(x) == Some(&true));
+
+    (0..width)
+        .group_by(|&x| lit(x))
+        .into_iter()
+        .filter(|(lit, _)| *lit)
+        .filter_map(|(_, mut columns)| {
+            let start = columns.next()?;
+            let end = columns.last().unwrap_or(start);
+            let glyph = pixels
+                .iter()
+                .map(|row| (start..=end).map(|x| row.get(x) == Some(&true)).collect())
+                .collect();
+            Some((start, glyph))
+        })
+        .collect()
+}
+
+fn draw(glyph: &[Vec<bool>]) -> String {
+    glyph
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|&p| if p { '#' } else { '.' })
+                .collect::<String>()
+        })
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_small_letters() -> Result<(), anyhow::Error> {
+        let image = "\
+####..##..###...##....##.####...##.####.
+...#.#..#.#..#.#..#....#.#.......#....#.
+..#..#....###..#..#....#.###.....#...#..
+.#...#....#..#.####....#.#.......#..#...
+#....#..#.#..#.#..#.#..#.#....#..#.#....
+####..##..###..#..#..##..#.....##..####.
+";
+        assert_eq!(read(image)?, "ZCBAJFJZ");
+        Ok(())
+    }
+
+    #[test]
+    fn reads_large_letters() -> Result<(), anyhow::Error> {
+        let image = "\
+........................
+#....#..#####...######..
+#....#..#....#..#.......
+.#..#...#....#..#.......
+.#..#...#....#..#.......
+..##....#####...#####...
+..##....#..#....#.......
+.#..#...#...#...#.......
+.#..#...#...#...#.......
+#....#..#....#..#.......
+#....#..#....#..######..
+";
+        assert_eq!(read(image)?, "XRE");
+        Ok(())
+    }
+
+    #[test]
+    fn every_letter_in_the_fonts_reads_back() -> Result<(), anyhow::Error> {
+        for (letters, font) in [SMALL, LARGE] {
+            assert_eq!(glyphs(&pixels(font)?).len(), letters.len());
+            assert_eq!(read(font)?, letters);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_letters_are_errors() {
+        let e = read("#.#\n###\n#.#\n#.#\n#.#\n#.#").map_err(|e| e.to_string());
+        assert_eq!(
+            e,
+            Err("Unknown letter at column 0:\n#.#\n###\n#.#\n#.#\n#.#\n#.#".to_owned())
+        );
+        assert!(read("#\n#\n#").is_err());
+        assert!(read("####\n#..x").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use std::fmt::Display;
+
+use nom::error::ErrorKind;
+
+/// A parse failure located within the input it happened in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Locates `remaining`, the unparsed tail of `source` where parsing stopped
+    #[must_use]
+    pub fn new(source: &str, remaining: &str, message: impl Into<String>) -> Self {
+        let offset = source.len().saturating_sub(remaining.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: source[line_start..]
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_owned(),
+            message: message.into(),
+        }
+    }
+
+    /// Moves an error located within `fragment`, a slice of `source`, to where it is in `source`
+    #[must_use]
+    pub fn within(self, source: &str, fragment: &str) -> Self {
+        let Some(start) = fragment
+            .as_ptr()
+            .addr()
+            .checked_sub(source.as_ptr().addr())
+            .filter(|&start| start + fragment.len() <= source.len())
+        else {
+            return self;
+        };
+        let before = &source[..start];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line = self.line + before.matches('\n').count();
+
+        Self {
+            line,
+            column: if self.line == 1 {
+                self.column + before[line_start..].chars().count()
+            } else {
+                self.column
+            },
+            text: source.lines().nth(line - 1).unwrap_or_default().to_owned(),
+            message: self.message,
+        }
+    }
+
+    pub(crate) fn from_nom(source: &str, e: &nom::Err<nom::error::Error<&str>>) -> Self {
+        match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Self::new(source, e.input, expected(e.code))
+            }
+            nom::Err::Incomplete(_) => Self::new(source, "", "unexpected end of input"),
+        }
+    }
+
+    /// Points at input that was left over after parsing, skipping any blank space before it
+    pub(crate) fn unparsed(source: &str, remaining: &str) -> Self {
+        Self::new(source, remaining.trim_start(), "unexpected input")
+    }
+}
+
+fn expected(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "expected a number".to_owned(),
+        ErrorKind::Alpha => "expected a letter".to_owned(),
+        ErrorKind::Char | ErrorKind::OneOf => "expected a character".to_owned(),
+        ErrorKind::CrLf => "expected a line ending".to_owned(),
+        ErrorKind::Eof => "expected the end of the input".to_owned(),
+        k => format!("{} failed", k.description()),
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let number = self.line.to_string();
+        let pad = " ".repeat(number.len());
+        writeln!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.line, self.column
+        )?;
+        writeln!(f, "{pad} |")?;
+        writeln!(f, "{number} | {}", self.text.trim_end_matches('\r'))?;
+        write!(f, "{pad} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        character::complete::{line_ending, u32},
+        multi::separated_list1,
+    };
+
+    use crate::final_parser;
+
+    use super::*;
+
+    #[test]
+    fn locates_errors() {
+        let error = ParseError::new("R 4\nU x\nL 3\n", "x\nL 3\n", "expected a number");
+        assert_eq!(
+            error,
+            ParseError {
+                line: 2,
+                column: 3,
+                text: "U x".to_owned(),
+                message: "expected a number".to_owned()
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "expected a number at line 2, column 3\n  |\n2 | U x\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn locates_errors_within_fragments() {
+        let source = "R 4\nU 1\n\nL 3\nD x\n";
+        let fragment = &source[9..];
+        let error = ParseError::new(fragment, &fragment[6..], "expected a number");
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = error.within(source, fragment);
+        assert_eq!((error.line, error.column), (5, 3));
+        assert_eq!(error.text, "D x");
+
+        let line = &source[11..12];
+        let error = ParseError::new(line, line, "expected a number").within(source, line);
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (4, 3, "L 3")
+        );
+
+        let elsewhere = ParseError::new("x", "x", "expected a number");
+        assert_eq!(elsewhere.clone().within(source, "x"), elsewhere);
+    }
+
+    #[test]
+    fn reports_unparsed_input() -> Result<(), anyhow::Error> {
+        let e = final_parser(separated_list1(line_ending, u32))("1\n2\nthree\n")
+            .err()
+            .ok_or_else(|| anyhow::anyhow!("parsed"))?;
+        let e = e.downcast::<ParseError>()?;
+        assert_eq!((e.line, e.column), (3, 1));
+        assert_eq!(e.text, "three");
+        Ok(())
+    }
+
+    #[test]
+    fn reports_nom_errors() -> Result<(), anyhow::Error> {
+        let e = final_parser(u32::<_, nom::error::Error<&str>>)("x")
+            .err()
+            .ok_or_else(|| anyhow::anyhow!("parsed"))?;
+        let e = e.downcast::<ParseError>()?;
+        assert_eq!((e.line, e.column), (1, 1));
+        assert_eq!(e.message, "expected a number");
+        Ok(())
+    }
+}
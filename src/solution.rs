@@ -0,0 +1,335 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    ops::RangeInclusive,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+use crate::{
+    allocations::{Allocations, Tracker},
+    cancel,
+    generate::Rng,
+    inputs::normalise,
+    Answer,
+};
+
+/// The error from generating an input for a day that has no generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoGenerator {
+    pub day: u32,
+}
+
+impl Display for NoGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "There is no input generator for day {}", self.day)
+    }
+}
+
+impl Error for NoGenerator {}
+
+/// A single day's puzzle, split into parsing the input and solving each part
+pub trait Solution: 'static {
+    const DAY: u32;
+    const TITLE: &'static str;
+
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, anyhow::Error>;
+    fn part_a(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error>;
+    fn part_b(input: &Self::Input<'_>) -> Result<Answer, anyhow::Error>;
+
+    /// Other ways of solving either part, which `runner compare` checks and times against
+    /// `part_a` and `part_b`
+    const ALTERNATIVES: &'static [Alternative<Self>] = &[];
+
+    /// The sizes `generate` makes inputs of, kept to what the day can still solve in seconds
+    const GENERATE_SIZES: RangeInclusive<usize> = 1..=1_000_000;
+
+    /// Makes up a valid input that grows with `size`, for measuring how the solution scales
+    fn generate(_size: usize, _rng: &mut Rng) -> Result<String, anyhow::Error> {
+        Err(NoGenerator { day: Self::DAY }.into())
+    }
+
+    /// Draws the puzzle as an SVG picture, for days where one helps to see what is going on
+    fn visualise(_input: &Self::Input<'_>) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Part {
+    A,
+    B,
+}
+
+impl Part {
+    pub const ALL: [Self; 2] = [Self::A, Self::B];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::A => write!(f, "a"),
+            Self::B => write!(f, "b"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" => Ok(Self::A),
+            "b" | "B" => Ok(Self::B),
+            _ => Err(anyhow!("unknown part '{s}', expected 'a' or 'b'")),
+        }
+    }
+}
+
+/// The name `part_a` and `part_b` go by among the other implementations of a part
+pub const DEFAULT_IMPLEMENTATION: &str = "default";
+
+/// Another way of solving one part, such as a simpler or a faster one, kept alongside the default
+/// so the two can be checked against each other
+pub struct Alternative<S: Solution + ?Sized> {
+    pub name: &'static str,
+    pub part: Part,
+    pub solve: for<'a, 'b> fn(&'b S::Input<'a>) -> Result<Answer, anyhow::Error>,
+}
+
+/// The answers from running some parts of a solution against one input, and how long each step
+/// took and what it allocated
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Duration,
+    pub parse_allocations: Allocations,
+    pub parts: Vec<PartRun>,
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: Result<Answer, anyhow::Error>,
+    pub duration: Duration,
+    pub allocations: Allocations,
+}
+
+/// Solves one part of a day from input that has already been parsed
+pub type Solver<'a> = dyn Fn(Part) -> Result<Answer, anyhow::Error> + 'a;
+
+/// Solves one part of a day from input that has already been parsed, with the named implementation
+pub type NamedSolver<'a> = dyn Fn(Part, &str) -> Result<Answer, anyhow::Error> + 'a;
+
+/// Object safe view of a [`Solution`] so that every day can be held in one registry
+pub trait DynSolution: Sync {
+    fn day(&self) -> u32;
+    fn title(&self) -> &'static str;
+    /// Normalises and parses the input, then solves one part
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, anyhow::Error>;
+    /// Normalises and parses the input once then solves each of the parts, timing every step and
+    /// counting its allocations
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, anyhow::Error>;
+    /// Parses already normalised input and throws the result away, so parsing can be measured alone
+    fn parse_only(&self, input: &str) -> Result<(), anyhow::Error>;
+    /// Parses already normalised input then hands `f` a way to solve either part from it
+    fn with_parsed(&self, input: &str, f: &mut dyn FnMut(&Solver<'_>))
+        -> Result<(), anyhow::Error>;
+    /// Makes up a valid input that grows with `size`, the same every time for the same seed
+    fn generate(&self, size: usize, seed: u64) -> Result<String, anyhow::Error>;
+    /// The sizes [`generate`](DynSolution::generate) makes inputs of
+    fn generate_sizes(&self) -> RangeInclusive<usize>;
+    /// The names of every implementation of a part, starting with the default
+    fn implementations(&self, part: Part) -> Vec<&'static str>;
+    /// Normalises and parses the input, then solves one part with the named implementation
+    fn solve_with(
+        &self,
+        input: &str,
+        part: Part,
+        implementation: &str,
+    ) -> Result<Answer, anyhow::Error>;
+    /// Parses already normalised input then hands `f` a way to solve either part from it with any
+    /// of its implementations
+    fn with_implementations(
+        &self,
+        input: &str,
+        f: &mut dyn FnMut(&NamedSolver<'_>),
+    ) -> Result<(), anyhow::Error>;
+    /// Normalises and parses the input, then draws it if the day has a visualisation
+    fn visualise(&self, input: &str) -> Result<Option<String>, anyhow::Error>;
+}
+
+impl<S: Solution + Sync> DynSolution for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn solve(&self, input: &str, part: Part) -> Result<Answer, anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let input = normalise(input);
+        let parsed = parse::<S>(&input)?;
+        solve_part::<S>(&parsed, part)
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let tracker = Tracker::start();
+        let start = Instant::now();
+        let input = normalise(input);
+        let parsed = parse::<S>(&input)?;
+        let parse = start.elapsed();
+        let parse_allocations = tracker.finish();
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let tracker = Tracker::start();
+                let start = Instant::now();
+                let answer = solve_part::<S>(&parsed, part);
+                let duration = start.elapsed();
+                PartRun {
+                    part,
+                    answer,
+                    duration,
+                    allocations: tracker.finish(),
+                }
+            })
+            .collect();
+
+        Ok(Run {
+            parse,
+            parse_allocations,
+            parts,
+        })
+    }
+
+    fn parse_only(&self, input: &str) -> Result<(), anyhow::Error> {
+        parse::<S>(input).map(drop)
+    }
+
+    fn with_parsed(
+        &self,
+        input: &str,
+        f: &mut dyn FnMut(&Solver<'_>),
+    ) -> Result<(), anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let parsed = parse::<S>(input)?;
+        f(&|part| solve_part::<S>(&parsed, part));
+        Ok(())
+    }
+
+    fn generate(&self, size: usize, seed: u64) -> Result<String, anyhow::Error> {
+        if !S::GENERATE_SIZES.contains(&size) {
+            return Err(anyhow!(
+                "Day {} generates inputs with a size from {} to {}",
+                S::DAY,
+                S::GENERATE_SIZES.start(),
+                S::GENERATE_SIZES.end()
+            ));
+        }
+        S::generate(size, &mut Rng::new(seed))
+    }
+
+    fn generate_sizes(&self) -> RangeInclusive<usize> {
+        S::GENERATE_SIZES
+    }
+
+    fn implementations(&self, part: Part) -> Vec<&'static str> {
+        std::iter::once(DEFAULT_IMPLEMENTATION)
+            .chain(
+                S::ALTERNATIVES
+                    .iter()
+                    .filter(|a| a.part == part)
+                    .map(|a| a.name),
+            )
+            .collect()
+    }
+
+    fn solve_with(
+        &self,
+        input: &str,
+        part: Part,
+        implementation: &str,
+    ) -> Result<Answer, anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let input = normalise(input);
+        let parsed = parse::<S>(&input)?;
+        solve_implementation::<S>(&parsed, part, implementation)
+    }
+
+    fn with_implementations(
+        &self,
+        input: &str,
+        f: &mut dyn FnMut(&NamedSolver<'_>),
+    ) -> Result<(), anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let parsed = parse::<S>(input)?;
+        f(&|part, implementation| solve_implementation::<S>(&parsed, part, implementation));
+        Ok(())
+    }
+
+    fn visualise(&self, input: &str) -> Result<Option<String>, anyhow::Error> {
+        let _day = info_span!("day", day = S::DAY).entered();
+        let input = normalise(input);
+        let parsed = parse::<S>(&input)?;
+        let _span = info_span!("visualise").entered();
+        Ok(S::visualise(&parsed))
+    }
+}
+
+/// Parses already normalised input within a span, so traces show it as a step of its own
+fn parse<S: Solution>(input: &str) -> Result<S::Input<'_>, anyhow::Error> {
+    let _span = info_span!("parse").entered();
+    S::parse(input)
+}
+
+/// Solves one part within a span named after it, starting the clock on its time budget
+fn solve_part<S: Solution>(parsed: &S::Input<'_>, part: Part) -> Result<Answer, anyhow::Error> {
+    let _span = match part {
+        Part::A => info_span!("part_a"),
+        Part::B => info_span!("part_b"),
+    }
+    .entered();
+    let _clock = cancel::start();
+    match part {
+        Part::A => S::part_a(parsed),
+        Part::B => S::part_b(parsed),
+    }
+}
+
+/// Solves one part with the named implementation, within the same span and budget as the default
+fn solve_implementation<S: Solution>(
+    parsed: &S::Input<'_>,
+    part: Part,
+    implementation: &str,
+) -> Result<Answer, anyhow::Error> {
+    if implementation == DEFAULT_IMPLEMENTATION {
+        return solve_part::<S>(parsed, part);
+    }
+    let alternative = S::ALTERNATIVES
+        .iter()
+        .find(|a| a.part == part && a.name == implementation)
+        .ok_or_else(|| {
+            anyhow!(
+                "day{:02}{part} has no implementation called '{implementation}'",
+                S::DAY
+            )
+        })?;
+    let _span = match part {
+        Part::A => info_span!("part_a", implementation),
+        Part::B => info_span!("part_b", implementation),
+    }
+    .entered();
+    let _clock = cancel::start();
+    (alternative.solve)(parsed)
+}
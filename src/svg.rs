@@ -0,0 +1,57 @@
+use std::fmt::Write;
+
+/// How many SVG units wide and high each pixel is drawn
+const SCALE: usize = 4;
+
+/// An SVG picture `width` by `height` pixels, each filled with the colour given for it or left
+/// transparent, scaled up so single pixels can be seen
+#[must_use]
+pub fn pixels(
+    width: usize,
+    height: usize,
+    colour: impl Fn(usize, usize) -> Option<String>,
+) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#,
+        width * SCALE,
+        height * SCALE
+    );
+    for y in 0..height {
+        for x in 0..width {
+            if let Some(fill) = colour(x, y) {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{x}" y="{y}" width="1" height="1" fill="{fill}"/>"#
+                );
+            }
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A picture drawn with `#` for lit pixels, such as day 10's CRT, with the lit pixels in `fill`
+#[must_use]
+pub fn picture(image: &str, fill: &str) -> String {
+    let rows: Vec<&[u8]> = image.lines().map(str::as_bytes).collect();
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    pixels(width, rows.len(), |x, y| {
+        (rows[y].get(x) == Some(&b'#')).then(|| fill.to_owned())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_lit_pixels() {
+        let svg = picture("#.\n.#", "black");
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"width="8" height="8" viewBox="0 0 2 2""#));
+        assert!(svg.contains(r#"<rect x="0" y="0" width="1" height="1" fill="black"/>"#));
+        assert!(svg.contains(r#"<rect x="1" y="1" width="1" height="1" fill="black"/>"#));
+        assert_eq!(svg.matches("<rect").count(), 2);
+        assert!(svg.ends_with("</svg>"));
+    }
+}
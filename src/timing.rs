@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+/// Durations collected over repeated runs of the same step
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timings(Vec<Duration>);
+
+impl Timings {
+    pub fn push(&mut self, duration: Duration) {
+        self.0.push(duration);
+    }
+
+    /// Adds the durations of another step to these, repeat by repeat, for steps that ran
+    /// separately but count as one
+    pub fn accumulate(&mut self, other: &Self) {
+        for (i, &d) in other.0.iter().enumerate() {
+            match self.0.get_mut(i) {
+                Some(total) => *total += d,
+                None => self.0.push(d),
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn min(&self) -> Duration {
+        self.0.iter().copied().min().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.0.clone();
+        sorted.sort_unstable();
+        match sorted.len() {
+            0 => Duration::ZERO,
+            n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+            n => sorted[n / 2],
+        }
+    }
+}
+
+/// Formats a duration with three significant figures in the most readable unit
+#[must_use]
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_secs_f64() * 1e9;
+    if nanos < 1e3 {
+        format!("{nanos:.0}ns")
+    } else if nanos < 1e6 {
+        format!("{:.2}µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.2}ms", nanos / 1e6)
+    } else {
+        format!("{:.2}s", nanos / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accumulates_repeat_by_repeat() {
+        let ms = Duration::from_millis;
+        let mut t = Timings::default();
+        t.push(ms(1));
+        t.push(ms(2));
+        let mut other = Timings::default();
+        for d in [10, 20, 30] {
+            other.push(ms(d));
+        }
+        t.accumulate(&other);
+        assert_eq!(t, Timings(vec![ms(11), ms(22), ms(30)]));
+    }
+
+    #[test]
+    fn min_and_median() {
+        let mut t = Timings::default();
+        for ms in [5, 1, 3, 9] {
+            t.push(Duration::from_millis(ms));
+        }
+        assert_eq!(t.min(), Duration::from_millis(1));
+        assert_eq!(t.median(), Duration::from_millis(4));
+        t.push(Duration::from_millis(2));
+        assert_eq!(t.median(), Duration::from_millis(3));
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Duration::from_nanos(12)), "12ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+    }
+}
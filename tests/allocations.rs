@@ -0,0 +1,21 @@
+//! Kept apart from the unit tests, which would otherwise allocate and free memory on other threads
+//! while this one counts
+
+use advent_of_code_2022::allocations::{CountingAllocator, Tracker};
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn counts_allocations() {
+    let tracker = Tracker::start();
+    let mut v = vec![0_u8; 1000];
+    v.extend([1; 1000]);
+    drop(v);
+    let allocations = tracker.finish();
+
+    // The vec starts with room for 1000 bytes, then grows to fit 2000. The test harness can
+    // allocate on its own thread at the same time, so allow for more.
+    assert!(allocations.count >= 2);
+    assert!(allocations.peak_bytes >= 2000);
+}
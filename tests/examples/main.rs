@@ -0,0 +1,68 @@
+//! Runs every example listed in `resources/examples.toml` against its day, one test per example,
+//! part and implementation, so a new example only needs a file and a manifest entry
+
+use std::{fs, path::Path, process::ExitCode};
+
+use advent_of_code_2022::{DEFAULT_IMPLEMENTATION, SOLUTIONS};
+use libtest_mimic::{Arguments, Failed, Trial};
+
+mod manifest;
+
+fn main() -> ExitCode {
+    let args = Arguments::from_args();
+
+    let examples = match manifest::discover(Path::new("resources")) {
+        Ok(examples) => examples,
+        Err(e) => {
+            eprintln!("unable to find the examples: {e:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // The default keeps the example's own name, and any other implementations are named after it
+    let mut trials = manifest::trials();
+    trials.extend(
+        examples
+            .into_iter()
+            .flat_map(|example| {
+                let implementations = SOLUTIONS
+                    .iter()
+                    .find(|s| s.day() == example.day)
+                    .map_or_else(
+                        || vec![DEFAULT_IMPLEMENTATION],
+                        |s| s.implementations(example.part),
+                    );
+                implementations
+                    .into_iter()
+                    .map(move |implementation| (example.clone(), implementation))
+            })
+            .map(|(example, implementation)| {
+                let name = if implementation == DEFAULT_IMPLEMENTATION {
+                    example.name()
+                } else {
+                    format!("{}/{implementation}", example.name())
+                };
+                Trial::test(name, move || {
+                    let s = SOLUTIONS
+                        .iter()
+                        .find(|s| s.day() == example.day)
+                        .ok_or_else(|| format!("no solution registered for day {}", example.day))?;
+                    let input = fs::read_to_string(&example.path).map_err(|e| e.to_string())?;
+                    let answer = s
+                        .solve_with(&input, example.part, implementation)
+                        .map_err(|e| format!("{e:#}"))?;
+
+                    if answer == example.expected {
+                        Ok(())
+                    } else {
+                        Err(Failed::from(format!(
+                            "expected {}\n     got {answer}",
+                            example.expected
+                        )))
+                    }
+                })
+            }),
+    );
+
+    libtest_mimic::run(&args, trials).exit_code()
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use advent_of_code_2022::{inputs::EXAMPLES_MANIFEST, Answer, Part};
+use libtest_mimic::Trial;
+
+/// The answers expected from one example file, either part left out when the example is not
+/// meant for it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Expected {
+    a: Option<Answer>,
+    b: Option<Answer>,
+}
+
+/// One part of one example, along with the answer it should give
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub day: u32,
+    pub file: String,
+    pub path: PathBuf,
+    pub part: Part,
+    pub expected: Answer,
+}
+
+impl Example {
+    /// A name that identifies the example and part, such as `day06/example06-2/a`
+    #[must_use]
+    pub fn name(&self) -> String {
+        let stem = self.file.strip_suffix(".txt").unwrap_or(&self.file);
+        format!("day{:02}/{stem}/{}", self.day, self.part)
+    }
+}
+
+/// Finds every `exampleNN*.txt` in a directory and pairs it with its answers from the manifest,
+/// failing if a file is missing from the manifest or the manifest lists a file that is missing
+pub fn discover(dir: &Path) -> Result<Vec<Example>, anyhow::Error> {
+    let manifest_path = dir.join(EXAMPLES_MANIFEST);
+    let manifest = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("unable to read {}", manifest_path.display()))?;
+    let mut manifest = parse(&manifest)
+        .with_context(|| format!("invalid examples in {}", manifest_path.display()))?;
+
+    let mut examples = Vec::new();
+    let mut files = fs::read_dir(dir)
+        .with_context(|| format!("unable to list examples in {}", dir.display()))?
+        .map(|e| Ok(e?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>, std::io::Error>>()?;
+    files.sort();
+
+    for file in files {
+        let Some(day) = day_of(&file) else {
+            continue;
+        };
+        let expected = manifest
+            .remove(&file)
+            .ok_or_else(|| anyhow!("{file} has no entry in {}", manifest_path.display()))?;
+
+        let parts = [(Part::A, expected.a), (Part::B, expected.b)];
+        for (part, expected) in parts {
+            if let Some(expected) = expected {
+                examples.push(Example {
+                    day,
+                    file: file.clone(),
+                    path: dir.join(&file),
+                    part,
+                    expected,
+                });
+            }
+        }
+    }
+
+    if let Some(file) = manifest.keys().next() {
+        return Err(anyhow!(
+            "{} lists {file}, which is not an example in {}",
+            manifest_path.display(),
+            dir.display()
+        ));
+    }
+    Ok(examples)
+}
+
+fn parse(content: &str) -> Result<BTreeMap<String, Expected>, anyhow::Error> {
+    Ok(toml::from_str(content)?)
+}
+
+/// The day an example file is for, from names like `example06.txt` or `example06-2.txt`
+#[must_use]
+pub fn day_of(file: &str) -> Option<u32> {
+    let rest = file.strip_prefix("example")?.strip_suffix(".txt")?;
+    let (day, suffix) = rest.split_at_checked(2)?;
+    if !day.bytes().all(|b| b.is_ascii_digit()) || !(suffix.is_empty() || suffix.starts_with('-')) {
+        return None;
+    }
+    day.parse().ok()
+}
+
+/// Checks of the manifest reader itself, run along with the examples as this test has no harness
+/// of its own to run them
+pub fn trials() -> Vec<Trial> {
+    vec![
+        Trial::test("manifest/days_from_file_names", || {
+            assert_eq!(day_of("example06.txt"), Some(6));
+            assert_eq!(day_of("example06-2.txt"), Some(6));
+            assert_eq!(day_of("example17.txt"), Some(17));
+            assert_eq!(day_of("input06.txt"), None);
+            assert_eq!(day_of("example6.txt"), None);
+            assert_eq!(day_of("example061.txt"), None);
+            assert_eq!(day_of(EXAMPLES_MANIFEST), None);
+            Ok(())
+        }),
+        Trial::test("manifest/parses_manifest", || {
+            let manifest = parse(
+                r#"
+["example09-2.txt"]
+b = "36"
+
+["example15.txt"]
+"#,
+            )?;
+            assert_eq!(
+                manifest["example09-2.txt"],
+                Expected {
+                    a: None,
+                    b: Some(36.into())
+                }
+            );
+            assert_eq!(manifest["example15.txt"], Expected::default());
+            assert!(parse("[\"example01.txt\"]\nc = \"1\"").is_err());
+            Ok(())
+        }),
+        Trial::test("manifest/every_example_is_listed", || {
+            let examples = discover(Path::new("resources"))?;
+            assert!(examples.iter().any(|e| e.name() == "day06/example06-2/a"));
+            assert!(examples
+                .windows(2)
+                .all(|w| (w[0].day, &w[0].file) <= (w[1].day, &w[1].file)));
+            Ok(())
+        }),
+    ]
+}